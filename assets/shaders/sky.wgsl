@@ -0,0 +1,23 @@
+#import bevy_pbr::mesh_view_bindings
+
+@group(1) @binding(0)
+var sky_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var sky_sampler: sampler;
+
+// vanilla wraps 1024 sky columns around a full turn and puts row 100 of the
+// sky on the horizon, with a projection of 160 pixels on a 320x200 screen
+@fragment
+fn fragment(
+    #import bevy_pbr::mesh_vertex_output
+) -> @location(0) vec4<f32> {
+    let dir = world_position.xyz - view.world_position;
+    let size = vec2<f32>(textureDimensions(sky_texture));
+
+    // world x is the negated map x
+    let angle = atan2(dir.z, -dir.x);
+    let column = angle / 6.2831853 * 1024.0;
+    let row = clamp(100.0 - 160.0 * dir.y / max(length(dir.xz), 0.0001), 0.0, size.y - 1.0);
+
+    return textureSample(sky_texture, sky_sampler, vec2<f32>(column / size.x, row / size.y));
+}
@@ -0,0 +1,205 @@
+use std::fmt::{
+    Display,
+    Result
+};
+
+use crate::lumps::{
+    flat::flat_dimensions,
+    png::is_png,
+};
+
+/// Map lumps, recognized by name since their content has no header
+pub const MAP_LUMPS: [&str; 14] = [
+    "THINGS",
+    "LINEDEFS",
+    "SIDEDEFS",
+    "VERTEXES",
+    "SEGS",
+    "SSECTORS",
+    "NODES",
+    "SECTORS",
+    "REJECT",
+    "BLOCKMAP",
+    "BEHAVIOR",
+    "TEXTMAP",
+    "ZNODES",
+    "ENDMAP",
+];
+
+/// DMX sound format number
+const DMX_FORMAT: u16 = 3;
+
+/// Lump type guessed from its content
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LumpType {
+    /// No data, like the markers
+    Empty,
+    /// DOOM picture with valid column offsets
+    Patch,
+    /// Raw flat of a known size
+    Flat,
+    /// DMX digital sound
+    DmxSound,
+    /// MUS music
+    Mus,
+    /// Standard MIDI file
+    Midi,
+    /// PNG picture
+    Png,
+    /// RIFF WAVE sound
+    Wav,
+    /// Ogg container
+    Ogg,
+    /// Printable text like DEHACKED or MAPINFO
+    Text,
+    /// Map geometry or scripts
+    MapData,
+    /// Not recognized
+    Unknown,
+}
+
+impl Display for LumpType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        let name = match self {
+            Self::Empty => "Empty",
+            Self::Patch => "Patch",
+            Self::Flat => "Flat",
+            Self::DmxSound => "DMX sound",
+            Self::Mus => "MUS",
+            Self::Midi => "MIDI",
+            Self::Png => "PNG",
+            Self::Wav => "WAV",
+            Self::Ogg => "OGG",
+            Self::Text => "Text",
+            Self::MapData => "Map data",
+            Self::Unknown => "Unknown",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Returns if `buffer` is a DMX sound whose samples fit in the lump
+fn is_dmx_sound(buffer: &[u8]) -> bool {
+    if buffer.len() < 8 {
+        return false;
+    }
+
+    let format = u16::from_le_bytes([buffer[0], buffer[1]]);
+    let samples = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+
+    format == DMX_FORMAT && samples > 0 && 8 + samples <= buffer.len()
+}
+
+/// Returns if `buffer` is a DOOM picture
+///
+/// Every column offset must point after the offsets table and its posts
+/// must end with 0xff inside the lump
+pub fn is_patch(buffer: &[u8]) -> bool {
+    if buffer.len() < 8 {
+        return false;
+    }
+
+    let width = i16::from_le_bytes([buffer[0], buffer[1]]);
+    let height = i16::from_le_bytes([buffer[2], buffer[3]]);
+
+    if width <= 0 || height <= 0 {
+        return false;
+    }
+
+    let table_end = 8 + 4 * width as usize;
+
+    if table_end > buffer.len() {
+        return false;
+    }
+
+    buffer[8..table_end].chunks_exact(4).all(|offset| {
+        let mut pos = u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize;
+
+        if pos < table_end {
+            return false;
+        }
+
+        // Each post is a top delta, a length, a padding byte, the pixels and another padding byte
+        while pos < buffer.len() {
+            if buffer[pos] == 0xff {
+                return true;
+            }
+
+            match buffer.get(pos + 1) {
+                Some(length) => pos += *length as usize + 4,
+                None => return false,
+            }
+        }
+
+        false
+    })
+}
+
+/// Returns if the lump size is the one of a flat
+fn is_flat(buffer: &[u8]) -> bool {
+    let (width, height) = flat_dimensions(buffer.len());
+
+    // Heretic/Hexen flats with an extra row and Hexen 64x128 flats
+    matches!(buffer.len(), 4160 | 8192) || (width == height && width * height == buffer.len())
+}
+
+/// Returns if `buffer` only holds printable text
+fn is_text(buffer: &[u8]) -> bool {
+    // Some editors pad text lumps with NUL or end them with a DOS EOF
+    let text = match std::str::from_utf8(buffer) {
+        Ok(text) => text.trim_end_matches(['\0', '\x1a']),
+        Err(_) => return false,
+    };
+
+    !text.is_empty() && text.chars().all(|c| !c.is_control() || c.is_ascii_whitespace())
+}
+
+/// Guess the lump type from its content, `name` is only needed for map lumps
+pub fn detect(name: &str, buffer: &[u8]) -> LumpType {
+    if buffer.is_empty() {
+        return LumpType::Empty;
+    }
+
+    if is_png(buffer) {
+        return LumpType::Png;
+    }
+
+    if buffer.starts_with(b"MUS\x1a") {
+        return LumpType::Mus;
+    }
+
+    if buffer.starts_with(b"MThd") {
+        return LumpType::Midi;
+    }
+
+    if buffer.starts_with(b"RIFF") && buffer.get(8..12) == Some(&b"WAVE"[..]) {
+        return LumpType::Wav;
+    }
+
+    if buffer.starts_with(b"OggS") {
+        return LumpType::Ogg;
+    }
+
+    if MAP_LUMPS.contains(&name) {
+        return LumpType::MapData;
+    }
+
+    if is_dmx_sound(buffer) {
+        return LumpType::DmxSound;
+    }
+
+    if is_patch(buffer) {
+        return LumpType::Patch;
+    }
+
+    if is_flat(buffer) {
+        return LumpType::Flat;
+    }
+
+    if is_text(buffer) {
+        return LumpType::Text;
+    }
+
+    LumpType::Unknown
+}
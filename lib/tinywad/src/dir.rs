@@ -3,24 +3,20 @@ use regex::Regex;
 use std::collections::{HashMap, LinkedList};
 
 use crate::{
+    detect::is_patch,
     error::WadError,
-    lump::{LumpAddKind, LumpData, LumpInfo, LumpKind, LumpState},
+    lump::{LumpAddKind, LumpData, LumpInfo, LumpKind, LumpState, Namespace},
     lumps::{
-        flat::Flat, music::lump::DoomMusic, palette::Palettes, patch::DoomImage, unknown::Unknown,
+        animated::Animated, flat::Flat, music::lump::DoomMusic, palette::Palettes,
+        patch::DoomImage, png::{is_png, PngImage}, switches::Switches, unknown::Unknown,
     },
     models::lump::Lump,
     wad::WadInfo,
 };
 
 lazy_static! {
-    /// Flat start lump name
-    static ref RE_F_START: Regex = Regex::new("F[0-9]+_START").unwrap();
-    /// Flat end lump name
-    static ref RE_F_END: Regex = Regex::new("F[0-9]+_END").unwrap();
-    /// Patch/Sprite start lump name
-    static ref RE_S_START: Regex = Regex::new("S[0-9]+_START").unwrap();
-    /// Patch/Sprite end lump name
-    static ref RE_S_END: Regex = Regex::new("S[0-9]+_END").unwrap();
+    /// Namespace marker lump name like `FF_START` or `P1_END`
+    static ref RE_MARKER: Regex = Regex::new("^([A-Z]+[0-9]?)_(START|END)$").unwrap();
     /// DOOM games lump name
     static ref RE_DOOM_MUSIC: Regex = Regex::new("^D_").unwrap();
 }
@@ -34,8 +30,8 @@ pub struct LumpsDirectory {
     pub lumps: Vec<Box<dyn Lump>>,
     /// Palette
     pub pal: Palettes,
-    /// Used during the directory parsing
-    marker: LinkedList<LumpKind>,
+    /// Opened namespaces, used during the directory parsing
+    marker: LinkedList<Namespace>,
 }
 
 impl Default for LumpsDirectory {
@@ -163,49 +159,63 @@ impl LumpsDirectory {
         Some(self.lumps.get(index.unwrap()).unwrap())
     }
 
+    /// Returns a lump by name within a namespace
+    ///
+    /// A flat and a patch can share a name, the namespace tells them apart
+    pub fn lump_in(&self, namespace: Namespace, name: &str) -> Option<&Box<dyn Lump>> {
+        self.lumps.iter().find(|lump| {
+            let data = lump.data();
+
+            data.namespace == namespace && data.metadata.name_ascii() == name
+        })
+    }
+
     /// Set the palette index
     pub fn set_palette(&mut self, value: usize) {
         self.pal.set_n(value % MAX_PAL);
     }
 
     /// Update the marker, handling the 0 bytes lumps like flat/patch delimiters
-    fn set_marker(&mut self, name: &str) {
-        if RE_F_START.is_match(name) {
-            self.marker.push_back(LumpKind::Flat);
-        }
-
-        if RE_S_START.is_match(name) {
-            self.marker.push_back(LumpKind::Patch);
-        }
-
-        if RE_F_END.is_match(name) {
-            self.marker.pop_back();
+    ///
+    /// Returns the namespace opened or closed by `name` if it is a marker
+    fn set_marker(&mut self, name: &str) -> Option<Namespace> {
+        let captures = RE_MARKER.captures(name)?;
+        let namespace = Namespace::from_prefix(&captures[1])?;
+
+        if &captures[2] == "START" {
+            self.marker.push_back(namespace);
+        } else if self.marker.contains(&namespace) {
+            // Closing a namespace also closes the ones left open inside it
+            while self.marker.pop_back() != Some(namespace) {}
         }
 
-        if RE_S_END.is_match(name) {
-            self.marker.pop_back();
-        }
+        Some(namespace)
     }
 
-    fn pop_marker(&mut self, metadata: &LumpInfo, name: &str, mut data: LumpData) -> Box<dyn Lump> {
-        self.set_marker(name);
+    /// Returns the namespace of the lumps being parsed
+    fn namespace(&self) -> Namespace {
+        self.marker.back().copied().unwrap_or_default()
+    }
 
-        if metadata.size <= 0 || self.marker.len() <= 0 {
+    /// Build a lump depending on the namespace it belongs to
+    fn namespaced_lump(&mut self, mut data: LumpData) -> Box<dyn Lump> {
+        if data.metadata.size <= 0 {
             return Box::new(Unknown { data });
         }
 
-        let last = self.marker.back().unwrap();
+        data.kind = data.namespace.kind();
 
-        match last {
-            LumpKind::Patch => {
-                data.kind = LumpKind::Patch;
+        // PNG pictures are decoded whatever the namespace, keeping its kind
+        if is_png(&data.buffer) {
+            return Box::new(PngImage::new(data));
+        }
 
+        match data.kind {
+            // Other picture formats are left alone
+            LumpKind::Patch | LumpKind::Texture | LumpKind::HiRes if is_patch(&data.buffer) => {
                 Box::new(DoomImage::new(self.pal.clone(), data))
             }
-            LumpKind::Flat => {
-                data.kind = LumpKind::Flat;
-                Box::new(Flat::new(self.pal.clone(), data))
-            }
+            LumpKind::Flat => Box::new(Flat::new(self.pal.clone(), data)),
             _ => Box::new(Unknown { data }),
         }
     }
@@ -248,8 +258,17 @@ impl LumpsDirectory {
                 buffer: buffer[pos..pos + size].to_vec(),
                 metadata,
                 kind: LumpKind::Unknown,
+                namespace: self.namespace(),
             };
 
+            // Markers only open or close a namespace
+            if let Some(namespace) = self.set_marker(&name) {
+                data.namespace = namespace;
+
+                self.lumps.push(Box::new(Unknown { data }));
+                continue;
+            }
+
             let mut lump: Box<dyn Lump> = match &*name {
                 "PLAYPAL" => {
                     data.kind = LumpKind::Palette;
@@ -261,31 +280,25 @@ impl LumpsDirectory {
                     Box::new(self.pal.clone())
                 }
 
-                "F_START" | "FF_START" => {
-                    self.marker.push_back(LumpKind::Flat);
-
-                    Box::new(Unknown { data })
-                }
+                "TITLEPIC" => Box::new(DoomImage::new(self.pal.clone(), data)),
 
-                "S_START" | "SS_START" => {
-                    self.marker.push_back(LumpKind::Patch);
+                "ANIMATED" => {
+                    data.kind = LumpKind::Animated;
 
-                    Box::new(Unknown { data })
+                    Box::new(Animated::new(data))
                 }
 
-                "F_END" | "FF_END" | "S_END" | "SS_END" => {
-                    self.marker.pop_back();
+                "SWITCHES" => {
+                    data.kind = LumpKind::Switches;
 
-                    Box::new(Unknown { data })
+                    Box::new(Switches::new(data))
                 }
 
-                "TITLEPIC" => Box::new(DoomImage::new(self.pal.clone(), data)),
-
                 _ => {
                     if RE_DOOM_MUSIC.is_match(&name) {
                         Box::new(DoomMusic::new(data))
                     } else {
-                        self.pop_marker(&metadata, &name, data)
+                        self.namespaced_lump(data)
                     }
                 }
             };
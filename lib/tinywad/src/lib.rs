@@ -12,5 +12,7 @@ pub mod lumps;
 pub mod properties;
 /// Lumps directory
 pub mod dir;
+/// Lump content detection
+pub mod detect;
 /// Build a new WAD file from a source one
 pub mod output;
@@ -1,3 +1,5 @@
+use crate::detect::{detect, LumpType};
+
 /// Lumps kind implementing the `Lump` trait
 #[derive(Clone, PartialEq, Copy)]
 pub enum LumpKind {
@@ -5,6 +7,14 @@ pub enum LumpKind {
     Sound,
     Patch,
     Palette,
+    /// Boom ANIMATED
+    Animated,
+    /// Boom SWITCHES
+    Switches,
+    /// Standalone texture between TX_START and TX_END
+    Texture,
+    /// High resolution replacement between HI_START and HI_END
+    HiRes,
     /// Unidentified lump
     Unknown
 }
@@ -15,6 +25,65 @@ impl Default for LumpKind {
     }
 }
 
+/// Lumps namespaces, delimited by the `*_START`/`*_END` markers
+#[derive(Clone, PartialEq, Eq, Hash, Copy, Debug)]
+pub enum Namespace {
+    /// Outside any marker
+    Global,
+    /// F_, FF_ and F1_ to F3_
+    Flats,
+    /// S_ and SS_
+    Sprites,
+    /// P_, PP_ and P1_ to P3_
+    Patches,
+    /// TX_
+    Textures,
+    /// C_ (Boom colormaps)
+    Colormaps,
+    /// A_ (ZDoom ACS libraries)
+    Acs,
+    /// V_ (Strife voices)
+    Voices,
+    /// HI_ (high resolution replacements)
+    HiRes,
+}
+
+impl Default for Namespace {
+    fn default() -> Self {
+        Self::Global
+    }
+}
+
+impl Namespace {
+    /// Get the namespace from a marker prefix like `FF` or `P1`
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        let ret = match prefix {
+            "F" | "FF" | "F1" | "F2" | "F3" => Self::Flats,
+            "S" | "SS" => Self::Sprites,
+            "P" | "PP" | "P1" | "P2" | "P3" => Self::Patches,
+            "TX" => Self::Textures,
+            "C" => Self::Colormaps,
+            "A" => Self::Acs,
+            "V" => Self::Voices,
+            "HI" => Self::HiRes,
+            _ => return None,
+        };
+
+        Some(ret)
+    }
+
+    /// Get the lump kind of the namespace members
+    pub fn kind(&self) -> LumpKind {
+        match self {
+            Self::Flats => LumpKind::Flat,
+            Self::Sprites | Self::Patches => LumpKind::Patch,
+            Self::Textures => LumpKind::Texture,
+            Self::HiRes => LumpKind::HiRes,
+            _ => LumpKind::Unknown,
+        }
+    }
+}
+
 /// Represents the lump state
 /// 
 /// It permits to organize the WAD operations (from `WadOp`)
@@ -173,7 +242,9 @@ pub struct LumpData {
     /// The lump metadata (16 bytes header)
     pub metadata: LumpInfo,
     /// The lump kind
-    pub kind: LumpKind
+    pub kind: LumpKind,
+    /// The namespace the lump belongs to
+    pub namespace: Namespace
 }
 
 impl Default for LumpData {
@@ -181,11 +252,19 @@ impl Default for LumpData {
         Self {
             buffer: Default::default(),
             metadata: Default::default(),
-            kind: LumpKind::Unknown
+            kind: LumpKind::Unknown,
+            namespace: Namespace::Global
         }
     }
 }
 
+impl LumpData {
+    /// Get the lump type guessed from its content
+    pub fn detected(&self) -> LumpType {
+        detect(&self.metadata.name_ascii(), &self.buffer)
+    }
+}
+
 impl Into<Vec<u8>> for LumpData {
     fn into(self) -> Vec<u8> {
         let mut ret = self.buffer;
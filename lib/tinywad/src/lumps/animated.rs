@@ -0,0 +1,199 @@
+use std::{
+    fmt::{Display, Result},
+    fs,
+};
+
+use crate::{lump::LumpData, models::lump::Lump};
+
+/// ANIMATED record size in bytes
+pub const ANIMATED_ENTRY_SIZE: usize = 23;
+/// Type byte marking the end of the ANIMATED lump
+pub const ANIMATED_END: u8 = 0xff;
+
+/// Read a 9 bytes null terminated name
+pub fn name_from_bytes(bytes: &[u8]) -> String {
+    let len = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..len]).to_string()
+}
+
+/// Write a name as 9 bytes null terminated (at most 8 characters)
+pub fn name_to_bytes(name: &str) -> [u8; 9] {
+    let mut array = [0; 9];
+
+    for (i, byte) in name.as_bytes().iter().take(8).enumerate() {
+        array[i] = *byte;
+    }
+
+    array
+}
+
+/// What an ANIMATED entry animates
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AnimatedKind {
+    Flat,
+    Texture,
+}
+
+/// Boom animation range (23 bytes)
+#[derive(Clone, Debug)]
+pub struct AnimatedEntry {
+    /// Flat or wall texture (bit 0 of the type byte)
+    pub kind: AnimatedKind,
+    /// Decals are allowed on the texture (bit 1 of the type byte)
+    pub decals: bool,
+    /// Last frame name (9 bytes)
+    pub last: String,
+    /// First frame name (9 bytes)
+    pub first: String,
+    /// Tics per frame (4 bytes) (little-endian)
+    pub speed: i32,
+}
+
+impl From<&[u8]> for AnimatedEntry {
+    fn from(bytes: &[u8]) -> Self {
+        Self {
+            kind: if bytes[0] & 1 != 0 {
+                AnimatedKind::Texture
+            } else {
+                AnimatedKind::Flat
+            },
+            decals: bytes[0] & 2 != 0,
+            last: name_from_bytes(&bytes[1..10]),
+            first: name_from_bytes(&bytes[10..19]),
+            speed: i32::from_le_bytes(bytes[19..23].try_into().unwrap_or_default()),
+        }
+    }
+}
+
+impl Into<Vec<u8>> for AnimatedEntry {
+    fn into(self) -> Vec<u8> {
+        let mut ret = Vec::new();
+        let mut kind = match self.kind {
+            AnimatedKind::Flat => 0,
+            AnimatedKind::Texture => 1,
+        };
+
+        if self.decals {
+            kind |= 2;
+        }
+
+        ret.push(kind);
+        ret.append(&mut name_to_bytes(&self.last).to_vec());
+        ret.append(&mut name_to_bytes(&self.first).to_vec());
+        ret.append(&mut i32::to_le_bytes(self.speed).to_vec());
+
+        ret
+    }
+}
+
+/// Represents the Boom ANIMATED lump
+#[derive(Clone)]
+pub struct Animated {
+    /// Animation ranges
+    pub entries: Vec<AnimatedEntry>,
+    /// Lump data
+    data: LumpData,
+}
+
+impl Animated {
+    pub fn new(data: LumpData) -> Self {
+        Self {
+            entries: Vec::new(),
+            data,
+        }
+    }
+
+    /// Serialize the entries, terminator included
+    pub fn buffer(&self) -> Vec<u8> {
+        let mut ret = Vec::new();
+
+        for entry in self.entries.iter() {
+            ret.append(&mut entry.clone().into());
+        }
+
+        ret.push(ANIMATED_END);
+        ret.append(&mut vec![0x00; ANIMATED_ENTRY_SIZE - 1]);
+
+        ret
+    }
+
+    /// Replace the entries then rebuild the lump buffer
+    pub fn set_entries(&mut self, entries: Vec<AnimatedEntry>) {
+        self.entries = entries;
+        self.data.buffer = self.buffer();
+        self.data.metadata.size = self.data.buffer.len() as i32;
+    }
+}
+
+impl Display for Animated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Name: {}, Size: {}, Offset: {}, Animations: {}",
+            self.data.metadata.id_ascii(),
+            self.data.metadata.size,
+            self.data.metadata.pos,
+            self.entries.len()
+        )?;
+
+        for entry in self.entries.iter() {
+            write!(
+                f,
+                "\n    {:?} {} -> {}, Speed: {}",
+                entry.kind, entry.first, entry.last, entry.speed
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Lump for Animated {
+    fn parse(&mut self) {
+        self.entries.clear();
+
+        for bytes in self.data.buffer.chunks_exact(ANIMATED_ENTRY_SIZE) {
+            if bytes[0] == ANIMATED_END {
+                break;
+            }
+
+            self.entries.push(AnimatedEntry::from(bytes));
+        }
+    }
+
+    /// Saved as a SWANTBLS like text file
+    fn save(&self, dir: &str) {
+        let path = format!("{}/{}.txt", dir, self.data.metadata.id_ascii());
+        let mut flats = String::from("[FLATS]\n");
+        let mut textures = String::from("[TEXTURES]\n");
+
+        for entry in self.entries.iter() {
+            let line = format!("{} {} {}\n", entry.speed, entry.last, entry.first);
+
+            match entry.kind {
+                AnimatedKind::Flat => flats.push_str(&line),
+                AnimatedKind::Texture => textures.push_str(&line),
+            }
+        }
+
+        fs::write(path, flats + "\n" + &textures).unwrap_or_default();
+    }
+
+    fn data(&self) -> LumpData {
+        self.data.clone()
+    }
+
+    fn set_data(&mut self, data: LumpData) {
+        self.data = data;
+    }
+
+    fn update(&mut self, buffer: &Vec<u8>) {
+        self.data.buffer = buffer.to_vec();
+        self.data.metadata.size = buffer.len() as i32;
+        self.parse();
+    }
+}
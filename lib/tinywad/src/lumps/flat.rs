@@ -21,11 +21,37 @@ pub const FLAT_H: usize = 64;
 /// Flat size
 pub const FLAT_SIZE: usize = FLAT_W * FLAT_H;
 
+/// Heretic and Hexen flats have an extra row that is never drawn
+const EXTRA_ROW_FLAT_SIZE: usize = FLAT_SIZE + FLAT_W;
+
+/// Infers the flat dimensions from the lump size
+///
+/// Square flats (64x64, 128x128, 256x256...) are recognized by their size,
+/// anything else is 64 wide, like the Hexen 64x128 flats, and short lumps
+/// are read as 64x64
+pub fn flat_dimensions(size: usize) -> (usize, usize) {
+    if size <= FLAT_SIZE || size == EXTRA_ROW_FLAT_SIZE {
+        return (FLAT_W, FLAT_H);
+    }
+
+    let side = (size as f64).sqrt() as usize;
+
+    if side * side == size && side.is_multiple_of(FLAT_W) {
+        (side, side)
+    } else {
+        (FLAT_W, size / FLAT_W)
+    }
+}
+
 /// Represents a Flat
 #[derive(Clone)]
 pub struct Flat {
     /// Array used to store the DOOM image data before converting it into bitmap
     pixels: Vec<u8>,
+    /// Flat width, from the lump size
+    width: usize,
+    /// Flat height, from the lump size
+    height: usize,
     /// Attached palettes
     palettes: Palettes,
     /// Lump data
@@ -37,12 +63,21 @@ impl Flat {
         palettes: Palettes,
         data: LumpData
     ) -> Self {
+        let (width, height) = flat_dimensions(data.buffer.len());
+
         Self {
             pixels: Vec::new(),
+            width,
+            height,
             palettes,
             data
         }
     }
+
+    /// Get the flat dimensions
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
 }
 
 impl Display for Flat {
@@ -53,8 +88,8 @@ impl Display for Flat {
             self.data.metadata.name_ascii(),
             self.data.metadata.size,
             self.data.metadata.pos,
-            FLAT_W,
-            FLAT_H
+            self.width,
+            self.height
         )
     }
 }
@@ -66,8 +101,11 @@ impl Lump for Flat {
             .palette()
             .unwrap();
         
-        for i in 0..FLAT_SIZE {
-            let byte = buffer[i];
+        self.pixels.clear();
+
+        // Short lumps are padded with the first palette color
+        for i in 0..self.width * self.height {
+            let byte = buffer.get(i).copied().unwrap_or(0);
             let (r, g, b, _) = palette[byte as usize].into();
         
             self.pixels.push(r);
@@ -87,8 +125,8 @@ impl Lump for Flat {
         image::save_buffer(
             Path::new(&path),
             &self.pixels,
-            FLAT_W as u32,
-            FLAT_H as u32,
+            self.width as u32,
+            self.height as u32,
             image::ColorType::Rgba8
         ).unwrap();
     }
@@ -98,6 +136,7 @@ impl Lump for Flat {
     }
 
     fn set_data(&mut self, data: LumpData) {
+        (self.width, self.height) = flat_dimensions(data.buffer.len());
         self.data = data;
     }
 
@@ -8,3 +8,9 @@ pub mod unknown;
 pub mod flat;
 /// Music
 pub mod music;
+/// Boom animated flats/textures
+pub mod animated;
+/// Boom switch textures
+pub mod switches;
+/// PNG picture
+pub mod png;
@@ -0,0 +1,146 @@
+use std::{
+    fmt::{
+        Display,
+        Result
+    },
+    fs,
+    path::Path,
+};
+
+use crate::{
+    models::lump::Lump,
+    lump::LumpData,
+};
+
+extern crate image;
+
+/// PNG file signature
+pub const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Returns if `buffer` holds a PNG file
+pub fn is_png(buffer: &[u8]) -> bool {
+    buffer.starts_with(&PNG_SIGNATURE)
+}
+
+/// Reads the `grAb` chunk, the picture offsets like the left/top of a DOOM picture
+pub fn grab_offset(buffer: &[u8]) -> Option<(i32, i32)> {
+    let mut pos = PNG_SIGNATURE.len();
+
+    // Every chunk is a big endian length, a type, the data and a CRC
+    while pos + 8 <= buffer.len() {
+        let length = u32::from_be_bytes(buffer[pos..pos + 4].try_into().ok()?) as usize;
+        let kind = &buffer[pos + 4..pos + 8];
+        let start = pos + 8;
+
+        match kind {
+            b"grAb" if length >= 8 && start + 8 <= buffer.len() => {
+                let x = i32::from_be_bytes(buffer[start..start + 4].try_into().ok()?);
+                let y = i32::from_be_bytes(buffer[start + 4..start + 8].try_into().ok()?);
+
+                return Some((x, y));
+            }
+            // The offsets come before the image data
+            b"IDAT" | b"IEND" => return None,
+            _ => pos = start + length + 4,
+        }
+    }
+
+    None
+}
+
+/// Reads the width and height from the `IHDR` chunk, which comes first
+pub fn png_size(buffer: &[u8]) -> Option<(u32, u32)> {
+    if buffer.get(12..16)? != b"IHDR" {
+        return None;
+    }
+
+    let width = u32::from_be_bytes(buffer.get(16..20)?.try_into().ok()?);
+    let height = u32::from_be_bytes(buffer.get(20..24)?.try_into().ok()?);
+
+    Some((width, height))
+}
+
+/// Represents a PNG picture stored in a lump
+#[derive(Clone)]
+pub struct PngImage {
+    /// Picture width
+    pub width: u32,
+    /// Picture height
+    pub height: u32,
+    /// Picture left/top offsets from the `grAb` chunk
+    pub offset: (i32, i32),
+    /// Lump data
+    data: LumpData,
+}
+
+impl PngImage {
+    pub fn new(data: LumpData) -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            offset: (0, 0),
+            data,
+        }
+    }
+
+    /// Get the final image buffer, structured as a RGBA format
+    ///
+    /// The pixels are only decoded here, a broken PNG gives an empty buffer
+    pub fn buffer(&self) -> Vec<u8> {
+        match image::load_from_memory_with_format(&self.data.buffer, image::ImageFormat::Png) {
+            Ok(image) => image.to_rgba8().into_raw(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+impl Display for PngImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Name: {}, Size: {}, Offset: {}, Width: {}, Height: {}",
+            self.data.metadata.name_ascii(),
+            self.data.metadata.size,
+            self.data.metadata.pos,
+            self.width,
+            self.height
+        )
+    }
+}
+
+impl Lump for PngImage {
+    fn parse(&mut self) {
+        let buffer = &*self.data.buffer;
+
+        self.offset = grab_offset(buffer).unwrap_or_default();
+
+        // Only the header is read, the pixels are decoded when asked for
+        (self.width, self.height) = png_size(buffer).unwrap_or_default();
+    }
+
+    fn save(&self, dir: &str) {
+        let path = format!(
+            "{}/{}.png",
+            dir,
+            self.data.metadata.name_ascii()
+        );
+
+        // The lump already is a PNG file
+        fs::write(Path::new(&path), &self.data.buffer).unwrap();
+    }
+
+    fn data(&self) -> LumpData {
+        self.data.clone()
+    }
+
+    fn set_data(&mut self, data: LumpData) {
+        self.data = data;
+    }
+
+    fn update(&mut self, buffer: &Vec<u8>) {
+        self.data.buffer = buffer.clone();
+        self.data.metadata.size = buffer.len() as i32;
+
+        self.parse();
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    fmt::{Display, Result},
+    fs,
+};
+
+use crate::{
+    lump::LumpData,
+    lumps::animated::{name_from_bytes, name_to_bytes},
+    models::lump::Lump,
+};
+
+/// SWITCHES record size in bytes
+pub const SWITCHES_ENTRY_SIZE: usize = 20;
+
+/// Boom switch texture pair (20 bytes)
+#[derive(Clone, Debug)]
+pub struct SwitchEntry {
+    /// Texture shown when the switch is off (9 bytes)
+    pub off: String,
+    /// Texture shown when the switch is on (9 bytes)
+    pub on: String,
+    /// 1 = shareware, 2 = registered, 3 = commercial (2 bytes) (little-endian)
+    ///
+    /// 0 ends the list
+    pub episode: i16,
+}
+
+impl From<&[u8]> for SwitchEntry {
+    fn from(bytes: &[u8]) -> Self {
+        Self {
+            off: name_from_bytes(&bytes[0..9]),
+            on: name_from_bytes(&bytes[9..18]),
+            episode: i16::from_le_bytes(bytes[18..20].try_into().unwrap_or_default()),
+        }
+    }
+}
+
+impl Into<Vec<u8>> for SwitchEntry {
+    fn into(self) -> Vec<u8> {
+        let mut ret = Vec::new();
+
+        ret.append(&mut name_to_bytes(&self.off).to_vec());
+        ret.append(&mut name_to_bytes(&self.on).to_vec());
+        ret.append(&mut i16::to_le_bytes(self.episode).to_vec());
+
+        ret
+    }
+}
+
+/// Represents the Boom SWITCHES lump
+#[derive(Clone)]
+pub struct Switches {
+    /// Switch pairs
+    pub entries: Vec<SwitchEntry>,
+    /// Lump data
+    data: LumpData,
+}
+
+impl Switches {
+    pub fn new(data: LumpData) -> Self {
+        Self {
+            entries: Vec::new(),
+            data,
+        }
+    }
+
+    /// Serialize the entries, terminator included
+    pub fn buffer(&self) -> Vec<u8> {
+        let mut ret = Vec::new();
+
+        for entry in self.entries.iter() {
+            ret.append(&mut entry.clone().into());
+        }
+
+        ret.append(&mut vec![0x00; SWITCHES_ENTRY_SIZE]);
+
+        ret
+    }
+
+    /// Replace the entries then rebuild the lump buffer
+    pub fn set_entries(&mut self, entries: Vec<SwitchEntry>) {
+        self.entries = entries;
+        self.data.buffer = self.buffer();
+        self.data.metadata.size = self.data.buffer.len() as i32;
+    }
+}
+
+impl Display for Switches {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result {
+        write!(
+            f,
+            "Name: {}, Size: {}, Offset: {}, Switches: {}",
+            self.data.metadata.id_ascii(),
+            self.data.metadata.size,
+            self.data.metadata.pos,
+            self.entries.len()
+        )?;
+
+        for entry in self.entries.iter() {
+            write!(
+                f,
+                "\n    {} <-> {}, Episode: {}",
+                entry.off, entry.on, entry.episode
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Lump for Switches {
+    fn parse(&mut self) {
+        self.entries.clear();
+
+        for bytes in self.data.buffer.chunks_exact(SWITCHES_ENTRY_SIZE) {
+            let entry = SwitchEntry::from(bytes);
+
+            if entry.episode == 0 {
+                break;
+            }
+
+            self.entries.push(entry);
+        }
+    }
+
+    /// Saved as a SWANTBLS like text file
+    fn save(&self, dir: &str) {
+        let path = format!("{}/{}.txt", dir, self.data.metadata.id_ascii());
+        let mut content = String::from("[SWITCHES]\n");
+
+        for entry in self.entries.iter() {
+            content.push_str(&format!("{} {} {}\n", entry.episode, entry.off, entry.on));
+        }
+
+        fs::write(path, content).unwrap_or_default();
+    }
+
+    fn data(&self) -> LumpData {
+        self.data.clone()
+    }
+
+    fn set_data(&mut self, data: LumpData) {
+        self.data = data;
+    }
+
+    fn update(&mut self, buffer: &Vec<u8>) {
+        self.data.buffer = buffer.to_vec();
+        self.data.metadata.size = buffer.len() as i32;
+        self.parse();
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::lump::LumpData;
 
-pub trait Lump: Display {
+pub trait Lump: Display + Send + Sync {
     /// parse the lump as a file if parseable
     fn parse(&mut self);
     /// Save the data as a file to the dir `dir`
@@ -9,8 +9,8 @@ use regex::{Error, Regex};
 use crate::{
     dir::LumpsDirectory,
     error::WadError,
-    lump::{LumpAdd, LumpAddKind, LumpData, LumpInfo, LumpKind},
-    lumps::unknown::Unknown,
+    lump::{LumpAdd, LumpAddKind, LumpData, LumpInfo, LumpKind, Namespace},
+    lumps::{animated::Animated, switches::Switches, unknown::Unknown},
     models::{lump::Lump, operation::WadOp},
     output::WadOutput,
     properties::file::PathWrap,
@@ -221,12 +221,44 @@ impl Wad {
     pub fn lump(&self, name: &str) -> Option<&Box<dyn Lump>> {
         self.dir.lump(name)
     }
+
+    /// Get a lump by its name within a namespace
+    pub fn lump_in(&self, namespace: Namespace, name: &str) -> Option<&Box<dyn Lump>> {
+        self.dir.lump_in(namespace, name)
+    }
+
+    /// Get the parsed Boom ANIMATED lump if there is one
+    pub fn animated(&self) -> Option<Animated> {
+        let lump = self.lump("ANIMATED")?;
+        let mut animated = Animated::new(lump.data());
+
+        animated.parse();
+
+        Some(animated)
+    }
+
+    /// Get the parsed Boom SWITCHES lump if there is one
+    pub fn switches(&self) -> Option<Switches> {
+        let lump = self.lump("SWITCHES")?;
+        let mut switches = Switches::new(lump.data());
+
+        switches.parse();
+
+        Some(switches)
+    }
+
+    /// Get every lump in directory order
+    pub fn lumps(&self) -> &Vec<Box<dyn Lump>> {
+        &self.dir.lumps
+    }
 }
 
 impl WadOp for Wad {
     fn dump(&self) {
         self.dir
-            .callback_lumps(self.re_name.clone(), |lump| println!("{}", lump));
+            .callback_lumps(self.re_name.clone(), |lump| {
+                println!("{}, Type: {}", lump, lump.data().detected())
+            });
     }
 
     fn save_lumps<P: AsRef<Path>>(&self, dir: P) {
@@ -301,6 +333,7 @@ impl WadOp for Wad {
                 buffer: add.buffer.clone(),
                 metadata,
                 kind: LumpKind::Unknown,
+                namespace: Namespace::Global,
             },
         };
 
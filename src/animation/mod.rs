@@ -0,0 +1,248 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::mapmanager::MapManager;
+use crate::state::GameState;
+use crate::tic::TicClock;
+use tinywad::lump::LumpKind;
+use tinywad::lumps::animated::AnimatedKind;
+
+struct AnimDef {
+    is_texture: bool,
+    end: String,
+    start: String,
+    speed: u64,
+}
+
+/// vanilla animdefs table from p_spec.c, used when there is no ANIMATED lump
+const VANILLA_ANIMS: [(bool, &str, &str, u64); 22] = [
+    (false, "NUKAGE3", "NUKAGE1", 8),
+    (false, "FWATER4", "FWATER1", 8),
+    (false, "SWATER4", "SWATER1", 8),
+    (false, "LAVA4", "LAVA1", 8),
+    (false, "BLOOD3", "BLOOD1", 8),
+    (false, "RROCK08", "RROCK05", 8),
+    (false, "SLIME04", "SLIME01", 8),
+    (false, "SLIME08", "SLIME05", 8),
+    (false, "SLIME12", "SLIME09", 8),
+    (true, "BLODGR4", "BLODGR1", 8),
+    (true, "SLADRIP3", "SLADRIP1", 8),
+    (true, "BLODRIP4", "BLODRIP1", 8),
+    (true, "FIREWALL", "FIREWALA", 8),
+    (true, "GSTFONT3", "GSTFONT1", 8),
+    (true, "FIRELAVA", "FIRELAV3", 8),
+    (true, "FIREMAG3", "FIREMAG1", 8),
+    (true, "FIREBLU2", "FIREBLU1", 8),
+    (true, "ROCKRED3", "ROCKRED1", 8),
+    (true, "BFALL4", "BFALL1", 8),
+    (true, "SFALL4", "SFALL1", 8),
+    (true, "WFALL4", "WFALL1", 8),
+    (true, "DBRAIN4", "DBRAIN1", 8),
+];
+
+struct TextureAnim {
+    /// frame names as they are keyed in the map data
+    frames: Vec<String>,
+    speed: u64,
+    step: u64,
+}
+
+#[derive(Resource)]
+pub struct TextureAnimations {
+    anims: Vec<TextureAnim>,
+    /// normalized frame name -> (animation, frame)
+    frame_index: HashMap<String, (usize, usize)>,
+    frame_images: HashMap<String, Handle<Image>>,
+}
+
+fn normalize(name: &str) -> String {
+    name.trim_end_matches("_masked")
+        .trim_end_matches("_flip")
+        .trim_matches(char::from(0))
+        .to_uppercase()
+}
+
+impl TextureAnimations {
+    pub fn new(manager: &MapManager) -> Self {
+        let mut defs: Vec<AnimDef> = Vec::new();
+
+        for wad in &manager.res_wads {
+            if let Some(animated) = wad.animated() {
+                for entry in animated.entries {
+                    defs.push(AnimDef {
+                        is_texture: entry.kind == AnimatedKind::Texture,
+                        end: entry.last.to_uppercase(),
+                        start: entry.first.to_uppercase(),
+                        speed: entry.speed.max(1) as u64,
+                    });
+                }
+                break;
+            }
+        }
+
+        if defs.is_empty() {
+            for (is_texture, end, start, speed) in VANILLA_ANIMS {
+                defs.push(AnimDef {
+                    is_texture,
+                    end: end.to_string(),
+                    start: start.to_string(),
+                    speed,
+                });
+            }
+        }
+
+        let mut animations = TextureAnimations {
+            anims: Vec::new(),
+            frame_index: HashMap::new(),
+            frame_images: HashMap::new(),
+        };
+
+        for def in defs {
+            let frames = if def.is_texture {
+                Self::texture_range(manager, &def)
+            } else {
+                Self::flat_range(manager, &def)
+            };
+
+            if frames.len() < 2 {
+                continue;
+            }
+
+            let anim_ind = animations.anims.len();
+
+            for (i, frame) in frames.iter().enumerate() {
+                animations
+                    .frame_index
+                    .insert(normalize(frame), (anim_ind, i));
+            }
+
+            animations.anims.push(TextureAnim {
+                frames,
+                speed: def.speed,
+                step: 0,
+            });
+        }
+
+        animations
+    }
+
+    /// every TEXTUREx entry between `start` and `end`, in definition order
+    fn texture_range(manager: &MapManager, def: &AnimDef) -> Vec<String> {
+        for names in &manager.map.texture_names {
+            let start = names.iter().position(|n| normalize(n) == def.start);
+            let end = names.iter().position(|n| normalize(n) == def.end);
+
+            if let (Some(start), Some(end)) = (start, end) {
+                if end > start {
+                    return names[start..=end].to_vec();
+                }
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// every flat lump between `start` and `end`, in directory order
+    fn flat_range(manager: &MapManager, def: &AnimDef) -> Vec<String> {
+        for wad in &manager.res_wads {
+            let lumps = wad.lumps();
+
+            let Some(start) = lumps
+                .iter()
+                .position(|l| l.data().metadata.name_ascii() == def.start)
+            else {
+                continue;
+            };
+
+            let Some(end) = lumps[start..]
+                .iter()
+                .position(|l| l.data().metadata.name_ascii() == def.end)
+            else {
+                continue;
+            };
+
+            return lumps[start..=start + end]
+                .iter()
+                .map(|l| l.data())
+                .filter(|data| data.kind == LumpKind::Flat && data.metadata.size > 0)
+                .map(|data| data.metadata.name())
+                .collect();
+        }
+
+        Vec::new()
+    }
+}
+
+fn animate_textures(
+    clock: Res<TicClock>,
+    mut animations: ResMut<TextureAnimations>,
+    mut manager: ResMut<MapManager>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if clock.tics == 0 {
+        return;
+    }
+
+    let mut changed = vec![false; animations.anims.len()];
+
+    for (i, anim) in animations.anims.iter_mut().enumerate() {
+        let step = clock.gametic / anim.speed;
+
+        if step != anim.step {
+            anim.step = step;
+            changed[i] = true;
+        }
+    }
+
+    if !changed.contains(&true) {
+        return;
+    }
+
+    let mut swaps: Vec<(Handle<StandardMaterial>, String)> = Vec::new();
+
+    for (key, material) in manager.mat_map.iter() {
+        if let Some(&(anim_ind, frame)) = animations.frame_index.get(&normalize(key)) {
+            if !changed[anim_ind] {
+                continue;
+            }
+
+            let anim = &animations.anims[anim_ind];
+            let target = (frame + anim.step as usize) % anim.frames.len();
+
+            swaps.push((material.clone(), anim.frames[target].clone()));
+        }
+    }
+
+    for (material, frame) in swaps {
+        let image = match animations.frame_images.get(&frame) {
+            Some(image) => image.clone(),
+            None => match manager.get_image(&mut images, frame.clone()) {
+                Ok(image) => {
+                    animations.frame_images.insert(frame, image.clone());
+                    image
+                }
+                Err(err) => {
+                    error!(err);
+                    continue;
+                }
+            },
+        };
+
+        if let Some(material) = materials.get_mut(&material) {
+            material.base_color_texture = Some(image);
+        }
+    }
+}
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            animate_textures
+                .run_if(resource_exists::<TextureAnimations>())
+                .in_set(OnUpdate(GameState::MapView)),
+        );
+    }
+}
@@ -0,0 +1,459 @@
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::mapmanager::complete_map::CompleteMap;
+use crate::mapmanager::MapManager;
+use crate::specials::{decode, Action, Key};
+use crate::state::GameState;
+use crate::things::is_actor;
+use crate::walk::WalkCamera;
+
+const WALL_COLOR: Color = Color::rgb(0.99, 0.0, 0.0);
+const FLOOR_CHANGE_COLOR: Color = Color::rgb(0.74, 0.48, 0.28);
+const CEILING_CHANGE_COLOR: Color = Color::rgb(0.99, 0.99, 0.45);
+const TWO_SIDED_COLOR: Color = Color::rgb(0.4, 0.4, 0.4);
+const SECRET_COLOR: Color = Color::rgb(0.8, 0.0, 0.8);
+const THING_COLOR: Color = Color::rgb(0.45, 0.99, 0.42);
+const GRID_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+const PLAYER_COLOR: Color = Color::WHITE;
+
+/// ML_SECRET and ML_DONTDRAW linedef flags
+const ML_SECRET: i16 = 32;
+const ML_DONTDRAW: i16 = 128;
+/// blockmap cell size, the grid spacing
+const GRID_SIZE: f32 = 128.;
+/// map units per pixel
+const MIN_SCALE: f32 = 0.05;
+const MAX_SCALE: f32 = 64.;
+/// map units per second at scale 1
+const PAN_SPEED: f32 = 600.;
+
+#[derive(Resource)]
+pub struct Automap {
+    pub enabled: bool,
+    pub follow: bool,
+    pub grid: bool,
+    /// map coordinates at the middle of the screen
+    pub center: Vec2,
+    /// map units per pixel
+    pub scale: f32,
+    rebuild: bool,
+}
+
+impl Default for Automap {
+    fn default() -> Self {
+        Automap {
+            enabled: false,
+            follow: true,
+            grid: false,
+            center: Vec2::ZERO,
+            scale: 2.,
+            rebuild: true,
+        }
+    }
+}
+
+#[derive(Component)]
+struct AutomapCamera;
+
+/// linedefs, things and grid
+#[derive(Component)]
+struct AutomapLines;
+
+/// arrow at the camera position
+#[derive(Component)]
+struct AutomapPlayer;
+
+type AutomapVisibilities<'w, 's> =
+    Query<'w, 's, &'static mut Visibility, Or<(With<AutomapLines>, With<AutomapPlayer>)>>;
+
+type AutomapEntities<'w, 's> =
+    Query<'w, 's, Entity, Or<(With<AutomapCamera>, With<AutomapLines>, With<AutomapPlayer>)>>;
+
+fn key_color(key: Key) -> Color {
+    match key {
+        Key::Blue | Key::BlueCard | Key::BlueSkull => Color::rgb(0.3, 0.3, 0.99),
+        Key::Yellow | Key::YellowCard | Key::YellowSkull => Color::rgb(0.99, 0.8, 0.0),
+        Key::Red | Key::RedCard | Key::RedSkull => Color::rgb(0.99, 0.4, 0.4),
+        Key::Any | Key::AllThree | Key::AllSix => Color::rgb(0.99, 0.55, 0.0),
+    }
+}
+
+/// colour of a linedef on the automap, None when it is not drawn
+pub(crate) fn line_color(map: &CompleteMap, line: usize) -> Option<Color> {
+    let linedef = &map.linedef_vec[line];
+
+    if linedef.flags & ML_DONTDRAW != 0 {
+        return None;
+    }
+
+    if let Some(special) = decode(linedef.special_type) {
+        if let Action::Door { key: Some(key), .. } = special.action {
+            return Some(key_color(key));
+        }
+    }
+
+    if linedef.flags & ML_SECRET != 0 {
+        return Some(SECRET_COLOR);
+    }
+
+    let (Some(front), Some(back)) = map.line_sectors(line) else {
+        return Some(WALL_COLOR);
+    };
+
+    let front = &map.sector_vec[front];
+    let back = &map.sector_vec[back];
+
+    Some(if front.floor_height != back.floor_height {
+        FLOOR_CHANGE_COLOR
+    } else if front.ceil_height != back.ceil_height {
+        CEILING_CHANGE_COLOR
+    } else {
+        TWO_SIDED_COLOR
+    })
+}
+
+/// coloured line segments, positions in map units
+#[derive(Default)]
+pub(crate) struct LineBuilder {
+    pub segments: Vec<(Vec2, Vec2, Color)>,
+}
+
+impl LineBuilder {
+    fn line(&mut self, from: Vec2, to: Vec2, color: Color) {
+        self.segments.push((from, to, color));
+    }
+
+    /// vanilla style arrow pointing along `angle`
+    fn arrow(&mut self, at: Vec2, angle: f32, size: f32, color: Color) {
+        let dir = Vec2::new(angle.cos(), angle.sin()) * size;
+        let tip = at + dir;
+
+        self.line(at - dir, tip, color);
+        self.line(tip, tip - dir.rotate(Vec2::new(0.7, 0.7)) * 0.5, color);
+        self.line(tip, tip - dir.rotate(Vec2::new(0.7, -0.7)) * 0.5, color);
+    }
+
+    fn dot(&mut self, at: Vec2, size: f32, color: Color) {
+        let corners = [
+            Vec2::new(-size, -size),
+            Vec2::new(size, -size),
+            Vec2::new(size, size),
+            Vec2::new(-size, size),
+        ];
+
+        for i in 0..4 {
+            self.line(at + corners[i], at + corners[(i + 1) % 4], color);
+        }
+    }
+
+    fn build(self) -> Mesh {
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        let mut colors: Vec<[f32; 4]> = Vec::new();
+
+        for (from, to, color) in self.segments {
+            positions.push([from.x, from.y, 0.]);
+            positions.push([to.x, to.y, 0.]);
+            colors.push(color.as_rgba_f32());
+            colors.push(color.as_rgba_f32());
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        mesh
+    }
+}
+
+/// grid, linedefs and things in draw order
+pub(crate) fn map_lines(map: &CompleteMap, grid: bool, things: bool) -> LineBuilder {
+    let mut builder = LineBuilder::default();
+
+    if grid && !map.vert_vec.is_empty() {
+        let (min, max) = map.vert_vec.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), vert| {
+                let point = Vec2::new(vert.x as f32, vert.y as f32);
+                (min.min(point), max.max(point))
+            },
+        );
+
+        let min = (min / GRID_SIZE).floor() * GRID_SIZE;
+        let max = (max / GRID_SIZE).ceil() * GRID_SIZE;
+
+        let mut x = min.x;
+        while x <= max.x {
+            builder.line(Vec2::new(x, min.y), Vec2::new(x, max.y), GRID_COLOR);
+            x += GRID_SIZE;
+        }
+
+        let mut y = min.y;
+        while y <= max.y {
+            builder.line(Vec2::new(min.x, y), Vec2::new(max.x, y), GRID_COLOR);
+            y += GRID_SIZE;
+        }
+    }
+
+    for i in 0..map.linedef_vec.len() {
+        let (Some(color), Some((start, end))) = (line_color(map, i), map.line_ends(i)) else {
+            continue;
+        };
+
+        builder.line(start, end, color);
+    }
+
+    for thing in map.things_vec.iter().filter(|_| things) {
+        let at = Vec2::new(thing.x as f32, thing.y as f32);
+
+        if is_actor(thing.thing_type) {
+            builder.arrow(at, (thing.angle as f32).to_radians(), 16., THING_COLOR);
+        } else {
+            builder.dot(at, 4., THING_COLOR);
+        }
+    }
+
+    builder
+}
+
+/// map position and facing of the 3D camera
+fn camera_on_map(transform: &GlobalTransform) -> (Vec2, f32) {
+    let position = transform.translation();
+    let forward = transform.forward();
+
+    (
+        Vec2::new(-position.x, position.z),
+        forward.z.atan2(-forward.x),
+    )
+}
+
+fn setup_automap(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(Automap::default());
+
+    commands
+        .spawn(Camera2dBundle {
+            camera: Camera {
+                is_active: false,
+                order: 1,
+                ..default()
+            },
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(Color::BLACK),
+            },
+            ..default()
+        })
+        .insert(AutomapCamera);
+
+    let material = color_materials.add(ColorMaterial::default());
+
+    commands
+        .spawn(MaterialMesh2dBundle {
+            mesh: meshes.add(LineBuilder::default().build()).into(),
+            material: material.clone(),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(AutomapLines);
+
+    commands
+        .spawn(MaterialMesh2dBundle {
+            mesh: meshes.add(LineBuilder::default().build()).into(),
+            material,
+            transform: Transform::from_xyz(0., 0., 1.),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(AutomapPlayer);
+}
+
+/// O toggles the automap, F follows the camera, G shows the grid
+fn automap_keys(
+    keys: Res<Input<KeyCode>>,
+    mut automap: ResMut<Automap>,
+    mut cameras: Query<&mut Camera, With<WalkCamera>>,
+    mut automap_cameras: Query<&mut Camera, (With<AutomapCamera>, Without<WalkCamera>)>,
+    mut visibilities: AutomapVisibilities,
+) {
+    if keys.just_pressed(KeyCode::O) {
+        automap.enabled = !automap.enabled;
+        automap.rebuild = true;
+
+        // the 3D view is hidden behind the automap anyway
+        for mut camera in cameras.iter_mut() {
+            camera.is_active = !automap.enabled;
+        }
+
+        for mut camera in automap_cameras.iter_mut() {
+            camera.is_active = automap.enabled;
+        }
+
+        for mut visibility in visibilities.iter_mut() {
+            *visibility = if automap.enabled {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+
+        info!("Automap {}", if automap.enabled { "on" } else { "off" });
+    }
+
+    if !automap.enabled {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::F) {
+        automap.follow = !automap.follow;
+        info!("Automap follow {}", if automap.follow { "on" } else { "off" });
+    }
+
+    if keys.just_pressed(KeyCode::G) {
+        automap.grid = !automap.grid;
+        automap.rebuild = true;
+    }
+}
+
+/// +/- and the mouse wheel zoom, arrows and right drag pan when not following
+fn automap_view(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut motion: EventReader<MouseMotion>,
+    mut automap: ResMut<Automap>,
+) {
+    if !automap.enabled {
+        wheel.clear();
+        motion.clear();
+        return;
+    }
+
+    let mut zoom = 1.;
+
+    for event in wheel.iter() {
+        let notches = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 20.,
+        };
+        zoom *= 1.1f32.powf(-notches);
+    }
+
+    if keys.pressed(KeyCode::Equals) {
+        zoom /= 1. + time.delta_seconds() * 2.;
+    }
+    if keys.pressed(KeyCode::Minus) {
+        zoom *= 1. + time.delta_seconds() * 2.;
+    }
+
+    automap.scale = (automap.scale * zoom).clamp(MIN_SCALE, MAX_SCALE);
+
+    let mut pan = Vec2::ZERO;
+
+    for event in motion.iter() {
+        if buttons.pressed(MouseButton::Right) {
+            pan += Vec2::new(-event.delta.x, event.delta.y) * automap.scale;
+        }
+    }
+
+    let mut direction = Vec2::ZERO;
+
+    if keys.pressed(KeyCode::Up) {
+        direction.y += 1.;
+    }
+    if keys.pressed(KeyCode::Down) {
+        direction.y -= 1.;
+    }
+    if keys.pressed(KeyCode::Right) {
+        direction.x += 1.;
+    }
+    if keys.pressed(KeyCode::Left) {
+        direction.x -= 1.;
+    }
+
+    pan += direction * PAN_SPEED * automap.scale * time.delta_seconds();
+
+    if pan != Vec2::ZERO && !automap.follow {
+        automap.center += pan;
+    }
+}
+
+fn draw_automap(
+    mut automap: ResMut<Automap>,
+    manager: Res<MapManager>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    cameras: Query<&GlobalTransform, With<WalkCamera>>,
+    mut automap_cameras: Query<(&mut Transform, &mut OrthographicProjection), With<AutomapCamera>>,
+    lines: Query<&Mesh2dHandle, With<AutomapLines>>,
+    players: Query<&Mesh2dHandle, With<AutomapPlayer>>,
+) {
+    if !automap.enabled {
+        return;
+    }
+
+    if automap.rebuild || manager.is_changed() {
+        automap.rebuild = false;
+
+        for handle in lines.iter() {
+            if let Some(mesh) = meshes.get_mut(&handle.0) {
+                *mesh = map_lines(&manager.map, automap.grid, true).build();
+            }
+        }
+    }
+
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+
+    let (position, angle) = camera_on_map(camera);
+
+    if automap.follow {
+        automap.center = position;
+    }
+
+    for handle in players.iter() {
+        if let Some(mesh) = meshes.get_mut(&handle.0) {
+            let mut builder = LineBuilder::default();
+            builder.arrow(position, angle, 16. * automap.scale.max(1.), PLAYER_COLOR);
+            *mesh = builder.build();
+        }
+    }
+
+    for (mut transform, mut projection) in automap_cameras.iter_mut() {
+        transform.translation.x = automap.center.x;
+        transform.translation.y = automap.center.y;
+        projection.scale = automap.scale;
+    }
+}
+
+fn cleanup_automap(
+    mut commands: Commands,
+    entities: AutomapEntities,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.remove_resource::<Automap>();
+}
+
+pub struct AutomapPlugin;
+
+impl Plugin for AutomapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_automap.in_schedule(OnEnter(GameState::MapView)))
+            .add_system(cleanup_automap.in_schedule(OnExit(GameState::MapView)))
+            .add_systems(
+                (automap_keys, automap_view, draw_automap)
+                    .chain()
+                    .distributive_run_if(resource_exists::<Automap>())
+                    .distributive_run_if(resource_exists::<MapManager>())
+                    .in_set(OnUpdate(GameState::MapView)),
+            );
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt::Write as _;
+use std::fs;
+
+use bevy::prelude::{Color, Vec2};
+use image::{Rgba, RgbaImage};
+
+use super::{ExportOptions, SectorFill};
+use crate::automap::map_lines;
+use crate::mapmanager::complete_map::CompleteMap;
+
+/// empty border around the map, in pixels
+const MARGIN: f32 = 16.;
+
+/// closed outlines of every sector, in map units
+fn sector_polygons(map: &CompleteMap) -> Vec<Vec<Vec<Vec2>>> {
+    map.sector_vec
+        .iter()
+        .map(|sector| {
+            map.detect_shapes(sector)
+                .iter()
+                .map(|shape| {
+                    map.get_linedef_vector_as_vertices(shape)
+                        .chunks_exact(2)
+                        .map(|xy| Vec2::new(xy[0] as f32, xy[1] as f32))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn fill_colors(map: &CompleteMap, fill: SectorFill) -> Vec<Color> {
+    let sectors = &map.sector_vec;
+
+    let lowest = sectors.iter().map(|s| s.floor_height).min().unwrap_or(0) as f32;
+    let highest = sectors.iter().map(|s| s.floor_height).max().unwrap_or(0) as f32;
+
+    sectors
+        .iter()
+        .map(|sector| match fill {
+            SectorFill::Height => {
+                let t = if highest > lowest {
+                    (sector.floor_height as f32 - lowest) / (highest - lowest)
+                } else {
+                    0.5
+                };
+                Color::rgb(0.1 + 0.4 * t, 0.1 + 0.4 * t, 0.2 + 0.6 * t)
+            }
+            SectorFill::Light => {
+                let level = sector.light_level.clamp(0, 255) as f32 / 255.;
+                Color::rgb(level * 0.6, level * 0.6, level * 0.6)
+            }
+        })
+        .collect()
+}
+
+fn bounds(map: &CompleteMap) -> (Vec2, Vec2) {
+    map.vert_vec.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), vert| {
+            let point = Vec2::new(vert.x as f32, vert.y as f32);
+            (min.min(point), max.max(point))
+        },
+    )
+}
+
+fn rgba8(color: Color) -> [u8; 4] {
+    color.as_rgba_f32().map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8)
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = rgba8(color);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// vector automap titled `name`, map units with y flipped to point down
+pub fn write_svg(map: &CompleteMap, name: &str, options: &ExportOptions) -> Result<(), String> {
+    let (min, max) = bounds(map);
+    let extent = (max - min).max(Vec2::ONE);
+    let margin = MARGIN * extent.max_element() / options.size as f32;
+
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">",
+        min.x - margin,
+        -max.y - margin,
+        extent.x + margin * 2.,
+        extent.y + margin * 2.,
+        ((extent.x + margin * 2.) / extent.max_element() * options.size as f32).round(),
+        ((extent.y + margin * 2.) / extent.max_element() * options.size as f32).round(),
+    );
+    let _ = writeln!(svg, "<title>{}</title>", name);
+    let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"100%\" height=\"100%\" fill=\"#000000\"/>", min.x - margin, -max.y - margin);
+
+    if let Some(fill) = options.fill {
+        let colors = fill_colors(map, fill);
+        let _ = writeln!(svg, "<g fill-rule=\"evenodd\" stroke=\"none\">");
+
+        for (i, polygons) in sector_polygons(map).iter().enumerate() {
+            let mut path = String::new();
+
+            for polygon in polygons.iter().filter(|polygon| polygon.len() >= 3) {
+                for (j, point) in polygon.iter().enumerate() {
+                    let _ = write!(path, "{}{} {} ", if j == 0 { "M" } else { "L" }, point.x, -point.y);
+                }
+                path.push('Z');
+            }
+
+            if !path.is_empty() {
+                let _ = writeln!(svg, "<path id=\"sector{}\" fill=\"{}\" d=\"{}\"/>", i, hex(colors[i]), path);
+            }
+        }
+
+        let _ = writeln!(svg, "</g>");
+    }
+
+    let _ = writeln!(svg, "<g stroke-width=\"1\" stroke-linecap=\"round\" vector-effect=\"non-scaling-stroke\">");
+
+    for (from, to, color) in map_lines(map, options.grid, options.things).segments {
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" vector-effect=\"non-scaling-stroke\"/>",
+            from.x,
+            -from.y,
+            to.x,
+            -to.y,
+            hex(color)
+        );
+    }
+
+    let _ = writeln!(svg, "</g>\n</svg>");
+
+    fs::write(&options.out, svg).map_err(|err| format!("Could not write {:?}: {}", options.out, err))
+}
+
+/// even-odd scanline fill, so holes of a sector stay empty
+fn fill_polygons(image: &mut RgbaImage, polygons: &[Vec<Vec2>], color: Rgba<u8>) {
+    let edges: Vec<(Vec2, Vec2)> = polygons
+        .iter()
+        .filter(|polygon| polygon.len() >= 3)
+        .flat_map(|polygon| {
+            polygon
+                .iter()
+                .zip(polygon.iter().cycle().skip(1))
+                .map(|(&a, &b)| (a, b))
+        })
+        .collect();
+
+    let top = edges.iter().map(|(a, b)| a.y.min(b.y)).fold(f32::MAX, f32::min).max(0.) as u32;
+    let bottom = edges.iter().map(|(a, b)| a.y.max(b.y)).fold(f32::MIN, f32::max).min(image.height() as f32) as u32;
+
+    for y in top..bottom {
+        let scan = y as f32 + 0.5;
+
+        let mut crossings: Vec<f32> = edges
+            .iter()
+            .filter(|(a, b)| (a.y <= scan) != (b.y <= scan))
+            .map(|(a, b)| a.x + (scan - a.y) / (b.y - a.y) * (b.x - a.x))
+            .collect();
+
+        crossings.sort_by(|a, b| a.total_cmp(b));
+
+        for pair in crossings.chunks_exact(2) {
+            let start = pair[0].round().max(0.) as u32;
+            let end = (pair[1].round().max(0.) as u32).min(image.width());
+
+            for x in start..end {
+                image.put_pixel(x, y, color);
+            }
+        }
+    }
+}
+
+fn draw_line(image: &mut RgbaImage, from: Vec2, to: Vec2, color: Rgba<u8>) {
+    let steps = (to - from).abs().max_element().ceil().max(1.) as u32;
+
+    for step in 0..=steps {
+        let point = from.lerp(to, step as f32 / steps as f32).round();
+
+        if point.x >= 0. && point.y >= 0. && (point.x as u32) < image.width() && (point.y as u32) < image.height() {
+            image.put_pixel(point.x as u32, point.y as u32, color);
+        }
+    }
+}
+
+/// raster automap, the longest side is `options.size` pixels
+pub fn write_png(map: &CompleteMap, options: &ExportOptions) -> Result<(), String> {
+    let (min, max) = bounds(map);
+    let extent = (max - min).max(Vec2::ONE);
+    let scale = (options.size as f32 - MARGIN * 2.).max(1.) / extent.max_element();
+
+    let to_pixel = |point: Vec2| {
+        Vec2::new(
+            (point.x - min.x) * scale + MARGIN,
+            (max.y - point.y) * scale + MARGIN,
+        )
+    };
+
+    let width = (extent.x * scale + MARGIN * 2.).ceil() as u32;
+    let height = (extent.y * scale + MARGIN * 2.).ceil() as u32;
+
+    let mut image = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+
+    if let Some(fill) = options.fill {
+        let colors = fill_colors(map, fill);
+
+        for (i, polygons) in sector_polygons(map).into_iter().enumerate() {
+            let polygons: Vec<Vec<Vec2>> = polygons
+                .into_iter()
+                .map(|polygon| polygon.into_iter().map(to_pixel).collect())
+                .collect();
+
+            fill_polygons(&mut image, &polygons, Rgba(rgba8(colors[i])));
+        }
+    }
+
+    for (from, to, color) in map_lines(map, options.grid, options.things).segments {
+        draw_line(&mut image, to_pixel(from), to_pixel(to), Rgba(rgba8(color)));
+    }
+
+    image
+        .save(&options.out)
+        .map_err(|err| format!("Could not write {:?}: {}", options.out, err))
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bevy::prelude::{Vec2, Vec3};
+use serde_json::{json, Value};
+
+use super::scene::{MapScene, Surface};
+use super::{file_name, ExportOptions};
+use crate::mapmanager::MapManager;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const NEAREST: u32 = 9728;
+const REPEAT: u32 = 10497;
+
+/// binary data and the views into it, everything else refers to these by index
+#[derive(Default)]
+struct Buffer {
+    data: Vec<u8>,
+    views: Vec<Value>,
+    accessors: Vec<Value>,
+}
+
+impl Buffer {
+    fn view(&mut self, bytes: &[u8], target: Option<u32>) -> usize {
+        while !self.data.len().is_multiple_of(4) {
+            self.data.push(0);
+        }
+
+        let mut view = json!({
+            "buffer": 0,
+            "byteOffset": self.data.len(),
+            "byteLength": bytes.len(),
+        });
+
+        if let Some(target) = target {
+            view["target"] = json!(target);
+        }
+
+        self.data.extend_from_slice(bytes);
+        self.views.push(view);
+        self.views.len() - 1
+    }
+
+    fn accessor(&mut self, bytes: &[u8], target: u32, component: u32, count: usize, kind: &str) -> usize {
+        let view = self.view(bytes, Some(target));
+
+        self.accessors.push(json!({
+            "bufferView": view,
+            "componentType": component,
+            "count": count,
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn vec3(&mut self, values: &[Vec3]) -> usize {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|v| v.to_array())
+            .flat_map(f32::to_le_bytes)
+            .collect();
+
+        self.accessor(&bytes, ARRAY_BUFFER, FLOAT, values.len(), "VEC3")
+    }
+
+    fn vec2(&mut self, values: &[Vec2]) -> usize {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|v| v.to_array())
+            .flat_map(f32::to_le_bytes)
+            .collect();
+
+        self.accessor(&bytes, ARRAY_BUFFER, FLOAT, values.len(), "VEC2")
+    }
+
+    fn indices(&mut self, values: &[u32]) -> usize {
+        let bytes: Vec<u8> = values.iter().flat_map(|i| i.to_le_bytes()).collect();
+
+        self.accessor(&bytes, ELEMENT_ARRAY_BUFFER, UNSIGNED_INT, values.len(), "SCALAR")
+    }
+}
+
+fn primitive(buffer: &mut Buffer, surface: &Surface, material: Option<usize>) -> Value {
+    let position = buffer.vec3(&surface.positions);
+
+    // positions need bounds
+    let (min, max) = surface.positions.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), &p| (min.min(p), max.max(p)),
+    );
+    buffer.accessors[position]["min"] = json!(min.to_array());
+    buffer.accessors[position]["max"] = json!(max.to_array());
+
+    let mut primitive = json!({
+        "attributes": {
+            "POSITION": position,
+            "NORMAL": buffer.vec3(&surface.normals),
+            "TEXCOORD_0": buffer.vec2(&surface.uvs),
+        },
+        "indices": buffer.indices(&surface.indices),
+    });
+
+    if let Some(material) = material {
+        primitive["material"] = json!(material);
+    }
+
+    primitive
+}
+
+/// glTF 2.0 with a node per sector, `.glb` packs everything in one file,
+/// `.gltf` writes a sidecar .bin and PNGs unless `--embed` is given
+pub fn write_gltf(manager: &mut MapManager, options: &ExportOptions, binary: bool) -> Result<(), String> {
+    let scene = MapScene::build(manager);
+
+    let out = &options.out;
+    let stem = out
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("map")
+        .to_string();
+    let dir = out.parent().unwrap_or(Path::new(""));
+    let texture_dir = format!("{}_textures", stem);
+
+    let mut buffer = Buffer::default();
+    let mut images: Vec<Value> = Vec::new();
+    let mut materials: Vec<Value> = Vec::new();
+    let mut material_ids: BTreeMap<&str, usize> = BTreeMap::new();
+
+    if !binary && !options.embed && !scene.textures.is_empty() {
+        fs::create_dir_all(dir.join(&texture_dir))
+            .map_err(|err| format!("Could not create {}: {}", texture_dir, err))?;
+    }
+
+    for (name, texture) in &scene.textures {
+        let png = texture.png()?;
+
+        let image = if binary {
+            json!({ "name": name, "bufferView": buffer.view(&png, None), "mimeType": "image/png" })
+        } else if options.embed {
+            json!({ "name": name, "uri": format!("data:image/png;base64,{}", STANDARD.encode(&png)) })
+        } else {
+            let uri = format!("{}/{}.png", texture_dir, file_name(name));
+            fs::write(dir.join(&uri), &png).map_err(|err| format!("Could not write {}: {}", uri, err))?;
+            json!({ "name": name, "uri": uri })
+        };
+
+        images.push(image);
+
+        let mut material = json!({
+            "name": name,
+            "pbrMetallicRoughness": {
+                "baseColorTexture": { "index": images.len() - 1 },
+                "metallicFactor": 0.0,
+                "roughnessFactor": 1.0,
+            },
+            "extensions": { "KHR_materials_unlit": {} },
+        });
+
+        if texture.masked {
+            material["alphaMode"] = json!("MASK");
+            material["alphaCutoff"] = json!(0.5);
+        }
+
+        materials.push(material);
+        material_ids.insert(name, materials.len() - 1);
+    }
+
+    let textures: Vec<Value> = (0..images.len())
+        .map(|image| json!({ "sampler": 0, "source": image }))
+        .collect();
+
+    let mut sectors: BTreeMap<usize, Vec<Value>> = BTreeMap::new();
+
+    for surface in &scene.surfaces {
+        let material = surface
+            .texture
+            .as_deref()
+            .and_then(|name| material_ids.get(name).copied());
+
+        let primitive = primitive(&mut buffer, surface, material);
+        sectors.entry(surface.sector).or_default().push(primitive);
+    }
+
+    let mut meshes: Vec<Value> = Vec::new();
+    let mut nodes: Vec<Value> = vec![json!({ "name": manager.map_name, "children": [] })];
+
+    for (sector, primitives) in sectors {
+        meshes.push(json!({ "name": format!("sector {}", sector), "primitives": primitives }));
+        nodes.push(json!({
+            "name": format!("sector {}", sector),
+            "mesh": meshes.len() - 1,
+            "extras": { "sector": sector },
+        }));
+    }
+
+    nodes[0]["children"] = json!((1..nodes.len()).collect::<Vec<usize>>());
+
+    while !buffer.data.len().is_multiple_of(4) {
+        buffer.data.push(0);
+    }
+
+    let mut root = json!({
+        "asset": { "version": "2.0", "generator": "bevytest export" },
+        "extensionsUsed": ["KHR_materials_unlit"],
+        "scene": 0,
+        "scenes": [{ "name": manager.map_name, "nodes": [0] }],
+        "nodes": nodes,
+        "meshes": meshes,
+        "materials": materials,
+        "textures": textures,
+        "images": images,
+        "samplers": [{
+            "magFilter": NEAREST,
+            "minFilter": NEAREST,
+            "wrapS": REPEAT,
+            "wrapT": REPEAT,
+        }],
+        "accessors": buffer.accessors,
+        "bufferViews": buffer.views,
+        "buffers": [{ "byteLength": buffer.data.len() }],
+    });
+
+    // glTF arrays need at least one item, and the sampler only serves textures
+    if let Some(root) = root.as_object_mut() {
+        if textures.is_empty() {
+            root.remove("samplers");
+        }
+
+        root.retain(|_, value| value.as_array().map_or(true, |array| !array.is_empty()));
+    }
+
+    if binary {
+        let mut json = serde_json::to_vec(&root).map_err(|err| err.to_string())?;
+        while !json.len().is_multiple_of(4) {
+            json.push(b' ');
+        }
+
+        let length = 12 + 8 + json.len() + 8 + buffer.data.len();
+
+        let mut glb: Vec<u8> = Vec::with_capacity(length);
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(&json);
+        glb.extend_from_slice(&(buffer.data.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&buffer.data);
+
+        return fs::write(out, glb).map_err(|err| format!("Could not write {:?}: {}", out, err));
+    }
+
+    root["buffers"][0]["uri"] = if options.embed {
+        json!(format!("data:application/octet-stream;base64,{}", STANDARD.encode(&buffer.data)))
+    } else {
+        let uri = format!("{}.bin", stem);
+        fs::write(dir.join(&uri), &buffer.data).map_err(|err| format!("Could not write {}: {}", uri, err))?;
+        json!(uri)
+    };
+
+    let json = serde_json::to_string_pretty(&root).map_err(|err| err.to_string())?;
+
+    fs::write(out, json).map_err(|err| format!("Could not write {:?}: {}", out, err))
+}
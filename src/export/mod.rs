@@ -0,0 +1,207 @@
+mod automap;
+mod gltf;
+mod obj;
+mod scene;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mapmanager::{wad_maps, MapManager};
+use crate::stats::MapStats;
+use tinywad::wad::Wad;
+
+/// What sectors are filled with on automap exports
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SectorFill {
+    Height,
+    Light,
+}
+
+pub struct ExportOptions {
+    pub iwad: String,
+    pub pwad: String,
+    /// map marker name or index
+    pub map: String,
+    pub out: PathBuf,
+    pub things: bool,
+    pub grid: bool,
+    pub fill: Option<SectorFill>,
+    /// longest side of raster images in pixels
+    pub size: u32,
+    /// put buffers and textures inside a .gltf instead of next to it
+    pub embed: bool,
+}
+
+const USAGE: &str = "usage: export <out.svg|out.png|out.gltf|out.glb|out.obj|out.json> --iwad <wad> [--pwad <wad>] \
+--map <name|index> [--things] [--grid] [--fill height|light] [--size <pixels>] [--embed]";
+
+impl ExportOptions {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = ExportOptions {
+            iwad: String::new(),
+            pwad: String::new(),
+            map: String::new(),
+            out: PathBuf::new(),
+            things: false,
+            grid: false,
+            fill: None,
+            size: 2048,
+            embed: false,
+        };
+
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .cloned()
+                    .ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE))
+            };
+
+            match arg.as_str() {
+                "--iwad" => options.iwad = value()?,
+                "--pwad" => options.pwad = value()?,
+                "--map" => options.map = value()?,
+                "--things" => options.things = true,
+                "--grid" => options.grid = true,
+                "--embed" => options.embed = true,
+                "--fill" => {
+                    options.fill = Some(match value()?.as_str() {
+                        "height" => SectorFill::Height,
+                        "light" => SectorFill::Light,
+                        other => return Err(format!("Unknown fill {}\n{}", other, USAGE)),
+                    })
+                }
+                "--size" => {
+                    options.size = value()?
+                        .parse()
+                        .map_err(|_| format!("--size needs a number\n{}", USAGE))?
+                }
+                _ if arg.starts_with("--") => return Err(format!("Unknown option {}\n{}", arg, USAGE)),
+                _ => options.out = PathBuf::from(arg),
+            }
+        }
+
+        if options.iwad.is_empty() || options.map.is_empty() || options.out.as_os_str().is_empty() {
+            return Err(USAGE.to_string());
+        }
+
+        // maps of the IWAD itself
+        if options.pwad.is_empty() {
+            options.pwad = options.iwad.clone();
+        }
+
+        Ok(options)
+    }
+}
+
+/// texture names can hold characters that paths and URIs do not like
+pub(crate) fn file_name(texture: &str) -> String {
+    texture
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// loads a map by marker name (MAP01, E1M1) or by index
+pub fn load_map(iwad: &str, pwad: &str, map: &str) -> Result<MapManager, String> {
+    for path in [iwad, pwad] {
+        if !Path::new(path).is_file() {
+            return Err(format!("Could not open {}", path));
+        }
+    }
+
+    let mut wad = Wad::new();
+    wad.load_from_file(pwad.to_string());
+
+    let maps = wad_maps(&wad);
+
+    let map_ind = maps
+        .iter()
+        .find(|(map_ind, name)| name.eq_ignore_ascii_case(map) || map_ind.to_string() == map)
+        .map(|(map_ind, _)| *map_ind)
+        .ok_or_else(|| {
+            let names: Vec<&str> = maps.iter().map(|(_, name)| name.as_str()).collect();
+            format!("No map {} in {}, it has {}", map, pwad, names.join(" "))
+        })?;
+
+    let mut manager = MapManager::new(iwad.to_string(), pwad.to_string(), map_ind);
+    manager.link_sector_linedefs();
+
+    Ok(manager)
+}
+
+/// `--iwad`, `--pwad` and `--map` of the commands that check maps,
+/// every map of the PWAD when no map is given
+pub fn map_args(args: &[String], usage: &str) -> Result<(String, String, Vec<String>), String> {
+    let (mut iwad, mut pwad, mut map) = (String::new(), String::new(), None);
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .cloned()
+            .ok_or_else(|| format!("{} needs a value\n{}", arg, usage))?;
+
+        match arg.as_str() {
+            "--iwad" => iwad = value,
+            "--pwad" => pwad = value,
+            "--map" => map = Some(value),
+            _ => return Err(format!("Unknown option {}\n{}", arg, usage)),
+        }
+    }
+
+    if iwad.is_empty() {
+        return Err(usage.to_string());
+    }
+
+    if pwad.is_empty() {
+        pwad = iwad.clone();
+    }
+
+    let maps = match map {
+        Some(map) => vec![map],
+        None => {
+            let mut wad = Wad::new();
+            wad.load_from_file(pwad.clone());
+            wad_maps(&wad).into_iter().map(|(_, name)| name).collect()
+        }
+    };
+
+    Ok((iwad, pwad, maps))
+}
+
+/// statistics report of the map
+fn write_stats(manager: &MapManager, options: &ExportOptions) -> Result<(), String> {
+    let report = MapStats::new(&manager.map).to_json(&manager.map_name);
+    let json = serde_json::to_string_pretty(&report).map_err(|err| err.to_string())?;
+
+    fs::write(&options.out, json).map_err(|err| format!("Could not write {:?}: {}", options.out, err))
+}
+
+/// `export` command line entry, picks the format from the output extension
+pub fn run(args: &[String]) -> Result<(), String> {
+    let options = ExportOptions::parse(args)?;
+    let mut manager = load_map(&options.iwad, &options.pwad, &options.map)?;
+
+    let extension = options
+        .out
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "svg" => automap::write_svg(&manager.map, &manager.map_name, &options),
+        "png" => automap::write_png(&manager.map, &options),
+        "gltf" => gltf::write_gltf(&mut manager, &options, false),
+        "glb" => gltf::write_gltf(&mut manager, &options, true),
+        "obj" => obj::write_obj(&mut manager, &options),
+        "json" => write_stats(&manager, &options),
+        _ => Err(format!("Unknown export format {:?}\n{}", options.out, USAGE)),
+    }?;
+
+    println!("Wrote {}", options.out.display());
+
+    Ok(())
+}
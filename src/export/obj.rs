@@ -0,0 +1,107 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use super::scene::MapScene;
+use super::{file_name, ExportOptions};
+use crate::mapmanager::MapManager;
+
+/// Wavefront OBJ with a group per sector and an MTL with a material per texture,
+/// the textures go next to it as PNGs
+pub fn write_obj(manager: &mut MapManager, options: &ExportOptions) -> Result<(), String> {
+    let scene = MapScene::build(manager);
+
+    let out = &options.out;
+    let stem = out
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("map")
+        .to_string();
+    let dir = out.parent().unwrap_or(Path::new(""));
+    let texture_dir = format!("{}_textures", stem);
+    let mtl_name = format!("{}.mtl", stem);
+
+    if !scene.textures.is_empty() {
+        fs::create_dir_all(dir.join(&texture_dir))
+            .map_err(|err| format!("Could not create {}: {}", texture_dir, err))?;
+    }
+
+    let mut mtl = String::new();
+
+    for (name, texture) in &scene.textures {
+        let uri = format!("{}/{}.png", texture_dir, file_name(name));
+
+        fs::write(dir.join(&uri), texture.png()?)
+            .map_err(|err| format!("Could not write {}: {}", uri, err))?;
+
+        let _ = writeln!(mtl, "newmtl {}", file_name(name));
+        let _ = writeln!(mtl, "Ka 0 0 0\nKd 1 1 1\nKs 0 0 0\nillum 1");
+        let _ = writeln!(mtl, "map_Kd {}", uri);
+
+        // see-through parts of masked mid textures come from the PNG alpha
+        if texture.masked {
+            let _ = writeln!(mtl, "map_d {}", uri);
+        }
+
+        mtl.push('\n');
+    }
+
+    // surfaces whose texture could not be found
+    let untextured = scene.surfaces.iter().any(|surface| {
+        !surface
+            .texture
+            .as_ref()
+            .is_some_and(|name| scene.textures.contains_key(name))
+    });
+
+    if untextured {
+        let _ = writeln!(mtl, "newmtl default\nKa 0 0 0\nKd 1 0 1\nKs 0 0 0\nillum 1");
+    }
+
+    let mut obj = String::new();
+    let _ = writeln!(obj, "# {}", manager.map_name);
+    let _ = writeln!(obj, "mtllib {}", mtl_name);
+
+    // OBJ indices are 1-based and shared by the whole file
+    let mut base = 1;
+    let mut sector = None;
+
+    for surface in &scene.surfaces {
+        if sector != Some(surface.sector) {
+            sector = Some(surface.sector);
+            let _ = writeln!(obj, "\no sector{}\ng sector{}", surface.sector, surface.sector);
+        }
+
+        match &surface.texture {
+            Some(name) if scene.textures.contains_key(name) => {
+                let _ = writeln!(obj, "usemtl {}", file_name(name));
+            }
+            _ => {
+                let _ = writeln!(obj, "usemtl default");
+            }
+        }
+
+        for position in &surface.positions {
+            let _ = writeln!(obj, "v {} {} {}", position.x, position.y, position.z);
+        }
+
+        // OBJ texture space starts at the bottom
+        for uv in &surface.uvs {
+            let _ = writeln!(obj, "vt {} {}", uv.x, 1. - uv.y);
+        }
+
+        for normal in &surface.normals {
+            let _ = writeln!(obj, "vn {} {} {}", normal.x, normal.y, normal.z);
+        }
+
+        for triangle in surface.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| i as usize + base);
+            let _ = writeln!(obj, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}");
+        }
+
+        base += surface.positions.len();
+    }
+
+    fs::write(dir.join(&mtl_name), mtl).map_err(|err| format!("Could not write {}: {}", mtl_name, err))?;
+    fs::write(out, obj).map_err(|err| format!("Could not write {:?}: {}", out, err))
+}
@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use bevy::ecs::system::CommandQueue;
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, VertexAttributeValues};
+use bevy::utils::HashMap;
+use image::{ImageOutputFormat, RgbaImage};
+
+use crate::lighting::LitSurface;
+use crate::mapmanager::components::FlatSurface;
+use crate::mapmanager::MapManager;
+
+/// all triangles of one sector that share a texture, in world space
+pub struct Surface {
+    pub sector: usize,
+    /// None when the texture could not be found
+    pub texture: Option<String>,
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub uvs: Vec<Vec2>,
+    pub indices: Vec<u32>,
+}
+
+/// a composed texture or flat as RGBA8
+pub struct TextureImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    /// drawn with its see-through pixels cut out, only two-sided middle textures are
+    pub masked: bool,
+}
+
+impl TextureImage {
+    pub fn png(&self) -> Result<Vec<u8>, String> {
+        let image = RgbaImage::from_raw(self.width, self.height, self.rgba.clone())
+            .ok_or_else(|| "Texture data does not match its size".to_string())?;
+
+        let mut png = Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, ImageOutputFormat::Png)
+            .map_err(|err| err.to_string())?;
+
+        Ok(png.into_inner())
+    }
+}
+
+/// the generated map geometry with its textures, built without a window
+pub struct MapScene {
+    pub surfaces: Vec<Surface>,
+    pub textures: BTreeMap<String, TextureImage>,
+}
+
+/// texture name as the WAD spells it, without padding or the ceiling suffix,
+/// masked uses keep theirs so they are exported apart from the opaque ones
+fn clean_name(name: &str) -> String {
+    let (name, masked) = match name.strip_suffix("_masked") {
+        Some(name) => (name, true),
+        None => (name, false),
+    };
+
+    let name = name.replace("_flip", "").trim_matches(char::from(0)).to_uppercase();
+
+    if masked {
+        format!("{}_MASKED", name)
+    } else {
+        name
+    }
+}
+
+fn attribute_vec3(mesh: &Mesh, attribute: impl Into<bevy::render::mesh::MeshVertexAttributeId>) -> Vec<Vec3> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Float32x3(values)) => values.iter().map(|v| Vec3::from(*v)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn attribute_vec2(mesh: &Mesh, attribute: impl Into<bevy::render::mesh::MeshVertexAttributeId>) -> Vec<Vec2> {
+    match mesh.attribute(attribute) {
+        Some(VertexAttributeValues::Float32x2(values)) => values.iter().map(|v| Vec2::from(*v)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn add_texture(
+    textures: &mut BTreeMap<String, TextureImage>,
+    images: &Assets<Image>,
+    name: &str,
+    image: &Handle<Image>,
+    masked: bool,
+) {
+    if let (false, Some(image)) = (textures.contains_key(name), images.get(image)) {
+        textures.insert(
+            name.to_string(),
+            TextureImage {
+                width: image.texture_descriptor.size.width,
+                height: image.texture_descriptor.size.height,
+                rgba: image.data.clone(),
+                masked,
+            },
+        );
+    }
+}
+
+impl MapScene {
+    /// spawns the walls and flats the way the map view does into a bare world
+    /// and reads the meshes back, so exports match what is drawn
+    pub fn build(manager: &mut MapManager) -> Self {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Mesh>()
+            .add_asset::<Image>()
+            .add_asset::<StandardMaterial>();
+
+        let world = &mut app.world;
+
+        let mut meshes = world.remove_resource::<Assets<Mesh>>().unwrap();
+        let mut images = world.remove_resource::<Assets<Image>>().unwrap();
+        let mut materials = world.remove_resource::<Assets<StandardMaterial>>().unwrap();
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+
+        for i in 0..manager.map.linedef_vec.len() {
+            manager.spawn_linedef_walls(&mut commands, &mut meshes, &mut images, &mut materials, i);
+        }
+
+        for sector_ind in 0..manager.map.sector_vec.len() {
+            manager.spawn_sector_flats(&mut commands, &mut meshes, &mut images, &mut materials, sector_ind);
+        }
+
+        queue.apply(world);
+
+        let material_names: HashMap<Handle<StandardMaterial>, String> = manager
+            .mat_map
+            .iter()
+            .map(|(name, material)| (material.clone(), clean_name(name)))
+            .collect();
+
+        let mut textures: BTreeMap<String, TextureImage> = BTreeMap::new();
+        let mut surfaces: BTreeMap<(usize, Option<String>), Surface> = BTreeMap::new();
+
+        let mut query = world.query::<(
+            &Handle<Mesh>,
+            &Transform,
+            Option<&Handle<StandardMaterial>>,
+            Option<&LitSurface>,
+            Option<&FlatSurface>,
+        )>();
+
+        for (mesh, transform, material, lit, flat) in query.iter(world) {
+            let Some(mesh) = meshes.get(mesh) else {
+                continue;
+            };
+
+            let Some(sector) = flat.map(|flat| flat.sector).or(lit.map(|lit| lit.sector)) else {
+                continue;
+            };
+
+            let texture = match (material, flat) {
+                (Some(material), _) => material_names.get(material).cloned().inspect(|name| {
+                    if let Some(material) = materials.get(material) {
+                        if let Some(image) = material.base_color_texture.as_ref() {
+                            let masked = matches!(material.alpha_mode, AlphaMode::Mask(_));
+                            add_texture(&mut textures, &images, name, image, masked);
+                        }
+                    }
+                }),
+                // sky ceilings get the sky flat itself
+                (None, Some(flat)) if flat.is_ceiling => {
+                    let raw = manager.map.sector_vec[sector].ceil_tex;
+                    let name = clean_name(std::str::from_utf8(&raw).unwrap_or_default());
+                    manager.get_image(&mut images, name.clone()).ok().map(|image| {
+                        add_texture(&mut textures, &images, &name, &image, false);
+                        name
+                    })
+                }
+                _ => None,
+            };
+
+            // map units are whole numbers, rounding drops the rotation noise of flats
+            let matrix = transform.compute_matrix();
+            let positions: Vec<Vec3> = attribute_vec3(mesh, Mesh::ATTRIBUTE_POSITION)
+                .into_iter()
+                .map(|position| matrix.transform_point3(position).round())
+                .collect();
+
+            if positions.is_empty() {
+                continue;
+            }
+
+            let mut uvs = attribute_vec2(mesh, Mesh::ATTRIBUTE_UV_0);
+            uvs.resize(positions.len(), Vec2::ZERO);
+
+            let mut indices: Vec<u32> = match mesh.indices() {
+                Some(Indices::U32(indices)) => indices.clone(),
+                Some(Indices::U16(indices)) => indices.iter().map(|&i| i as u32).collect(),
+                None => (0..positions.len() as u32).collect(),
+            };
+
+            // ceilings are drawn with front faces culled, turn them to face down
+            if flat.is_some_and(|flat| flat.is_ceiling) {
+                for triangle in indices.chunks_exact_mut(3) {
+                    triangle.swap(1, 2);
+                }
+            }
+
+            // every generated mesh is planar, so one normal covers it
+            let normal = indices
+                .chunks_exact(3)
+                .map(|t| {
+                    let [a, b, c] = [t[0], t[1], t[2]].map(|i| positions[i as usize]);
+                    (b - a).cross(c - a)
+                })
+                .sum::<Vec3>()
+                .normalize_or_zero();
+
+            let surface = surfaces
+                .entry((sector, texture.clone()))
+                .or_insert_with(|| Surface {
+                    sector,
+                    texture,
+                    positions: Vec::new(),
+                    normals: Vec::new(),
+                    uvs: Vec::new(),
+                    indices: Vec::new(),
+                });
+
+            let base = surface.positions.len() as u32;
+            surface.indices.extend(indices.iter().map(|i| i + base));
+            surface.normals.extend(std::iter::repeat_n(normal, positions.len()));
+            surface.positions.extend(positions);
+            surface.uvs.extend(uvs);
+        }
+
+        manager.tex_map.clear();
+        manager.mat_map.clear();
+
+        MapScene {
+            surfaces: surfaces.into_values().collect(),
+            textures,
+        }
+    }
+}
@@ -1,13 +1,24 @@
+use tinywad::lumps::flat::flat_dimensions;
 use tinywad::lumps::palette::{Palette, Palettes};
 
 pub struct Flat {
-    buffer: [u8; 4096],
+    pub width: usize,
+    pub height: usize,
+    buffer: Vec<u8>,
 }
 
 impl Flat {
+    /// flat of any size the lump allows, short lumps are padded with color 0
     pub fn from_lump(data: &[u8]) -> Flat {
+        let (width, height) = flat_dimensions(data.len());
+
+        let mut buffer = data.to_vec();
+        buffer.resize(width * height, 0);
+
         Flat {
-            buffer: data.clone().try_into().unwrap(),
+            width,
+            height,
+            buffer,
         }
     }
 
@@ -0,0 +1,438 @@
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::window::PrimaryWindow;
+
+use crate::mapmanager::complete_map::{trimmed, CompleteMap};
+use crate::mapmanager::components::{FlatSurface, Side, ThingEntity, WallPart, WallSurface};
+use crate::mapmanager::MapManager;
+use crate::specials::describe;
+use crate::state::GameState;
+use crate::walk::Walker;
+
+/// tint multiplied into the texture of the selected surfaces
+const HIGHLIGHT_COLOR: Color = Color::rgb(1., 0.45, 0.1);
+
+const LINEDEF_FLAGS: [&str; 10] = [
+    "Impassable",
+    "Block monsters",
+    "Two sided",
+    "Upper unpegged",
+    "Lower unpegged",
+    "Secret",
+    "Block sound",
+    "Not on map",
+    "Already on map",
+    "Pass use",
+];
+
+const THING_FLAGS: [&str; 8] = [
+    "Easy",
+    "Medium",
+    "Hard",
+    "Ambush",
+    "Multiplayer only",
+    "Not in deathmatch",
+    "Not in coop",
+    "Friendly",
+];
+
+/// Map element under the cursor when it was clicked
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Picked {
+    Wall {
+        linedef: usize,
+        side: Side,
+        part: WallPart,
+    },
+    Flat { sector: usize, is_ceiling: bool },
+    Thing { index: usize },
+}
+
+#[derive(Resource, Default)]
+pub struct Selection {
+    pub picked: Option<Picked>,
+    /// highlighted entities and the material they had before
+    highlighted: Vec<(Entity, Handle<StandardMaterial>)>,
+}
+
+/// meshes the picking ray is tested against
+type Pickable<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Handle<Mesh>,
+        &'static GlobalTransform,
+        Option<&'static WallSurface>,
+        Option<&'static FlatSurface>,
+        Option<&'static ThingEntity>,
+    ),
+    Or<(With<WallSurface>, With<FlatSurface>, With<ThingEntity>)>,
+>;
+
+type Highlightable<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Handle<StandardMaterial>,
+        Option<&'static WallSurface>,
+        Option<&'static FlatSurface>,
+        Option<&'static ThingEntity>,
+    ),
+>;
+
+#[derive(Component)]
+struct InspectorPanel;
+
+#[derive(Component)]
+struct InspectorText;
+
+fn picked_of(
+    wall: Option<&WallSurface>,
+    flat: Option<&FlatSurface>,
+    thing: Option<&ThingEntity>,
+) -> Option<Picked> {
+    if let Some(wall) = wall {
+        Some(Picked::Wall {
+            linedef: wall.linedef,
+            side: wall.side,
+            part: wall.part,
+        })
+    } else if let Some(flat) = flat {
+        Some(Picked::Flat {
+            sector: flat.sector,
+            is_ceiling: flat.is_ceiling,
+        })
+    } else {
+        thing.map(|thing| Picked::Thing { index: thing.index })
+    }
+}
+
+/// Möller–Trumbore, distance along the ray to the triangle
+fn ray_triangle(ray: &Ray, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+
+    if det.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let t_vec = ray.origin - a;
+    let u = t_vec.dot(p) / det;
+
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+
+    let q = t_vec.cross(edge1);
+    let v = ray.direction.dot(q) / det;
+
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+
+    let distance = edge2.dot(q) / det;
+
+    (distance > 0.).then_some(distance)
+}
+
+/// nearest hit of the ray on a triangle list mesh placed by `transform`
+fn ray_mesh(ray: &Ray, mesh: &Mesh, transform: &GlobalTransform) -> Option<f32> {
+    let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+        return None;
+    };
+
+    let matrix = transform.compute_matrix();
+    let positions: Vec<Vec3> = positions
+        .iter()
+        .map(|&position| matrix.transform_point3(Vec3::from(position)))
+        .collect();
+
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    indices
+        .chunks_exact(3)
+        .filter_map(|tri| ray_triangle(ray, positions[tri[0]], positions[tri[1]], positions[tri[2]]))
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+fn flag_names(flags: i16, names: &[&str]) -> String {
+    let set: Vec<&str> = names
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect();
+
+    if set.is_empty() {
+        "none".to_string()
+    } else {
+        set.join(", ")
+    }
+}
+
+fn sector_text(map: &CompleteMap, index: usize) -> String {
+    let Some(sector) = map.sector_vec.get(index) else {
+        return format!("Sector {} is missing", index);
+    };
+
+    format!(
+        "Sector {}\n  Floor {} {}\n  Ceiling {} {}\n  Light {}\n  Special {}\n  Tag {}",
+        index,
+        sector.floor_height,
+        trimmed(&sector.floor_tex),
+        sector.ceil_height,
+        trimmed(&sector.ceil_tex),
+        sector.light_level,
+        sector.special,
+        sector.tag,
+    )
+}
+
+/// inspector text of a picked element
+fn selection_text(map: &CompleteMap, picked: Picked) -> String {
+    match picked {
+        Picked::Wall {
+            linedef,
+            side,
+            part,
+        } => {
+            let line = &map.linedef_vec[linedef];
+            let sidedef_index = match side {
+                Side::Front => line.front_sidedef,
+                Side::Back => line.back_sidedef,
+            };
+
+            let mut text = format!(
+                "Linedef {}\n  Vertices {} -> {}\n  Flags {}\n  Special {}\n  Tag {}",
+                linedef,
+                line.start_vert,
+                line.end_vert,
+                flag_names(line.flags, &LINEDEF_FLAGS),
+                describe(line.special_type),
+                line.sector_tag,
+            );
+
+            if let Some(sidedef) = usize::try_from(sidedef_index).ok().and_then(|i| map.sidefef_vec.get(i)) {
+
+                text += &format!(
+                    "\n\nSidedef {} ({:?}, {:?})\n  Offsets {}, {}\n  Upper {}\n  Middle {}\n  Lower {}\n\n{}",
+                    sidedef_index,
+                    side,
+                    part,
+                    sidedef.x_off,
+                    sidedef.y_off,
+                    trimmed(&sidedef.upper_tex),
+                    trimmed(&sidedef.mid_tex),
+                    trimmed(&sidedef.lower_tex),
+                    sector_text(map, sidedef.sector as usize),
+                );
+            }
+
+            text
+        }
+        Picked::Flat { sector, is_ceiling } => format!(
+            "{} of {}",
+            if is_ceiling { "Ceiling" } else { "Floor" },
+            sector_text(map, sector)
+        ),
+        Picked::Thing { index } => {
+            let thing = &map.things_vec[index];
+
+            let mut text = format!(
+                "Thing {}\n  Type {}\n  Position {}, {}\n  Angle {}\n  Flags {}",
+                index,
+                thing.thing_type,
+                thing.x,
+                thing.y,
+                thing.angle,
+                flag_names(thing.flags, &THING_FLAGS),
+            );
+
+            if let Some(sector) = map.sector_at(Vec2::new(thing.x as f32, thing.y as f32)) {
+                text += &format!("\n\n{}", sector_text(map, sector));
+            }
+
+            text
+        }
+    }
+}
+
+fn setup_inspector(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Selection::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(10.),
+                    top: Val::Px(10.),
+                    ..default()
+                },
+                padding: UiRect::all(Val::Px(8.)),
+                display: Display::None,
+                ..default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.7).into(),
+            ..default()
+        })
+        .insert(InspectorPanel)
+        .with_children(|panel| {
+            panel
+                .spawn(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("FiraMono-Medium.ttf"),
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(InspectorText);
+        });
+}
+
+fn clear_selection(keys: Res<Input<KeyCode>>, mut selection: ResMut<Selection>) {
+    if keys.just_pressed(KeyCode::Escape) && selection.picked.is_some() {
+        selection.picked = None;
+    }
+}
+
+/// click picks the nearest wall, flat or thing under the cursor
+fn pick(
+    buttons: Res<Input<MouseButton>>,
+    walker: Res<Walker>,
+    meshes: Res<Assets<Mesh>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    surfaces: Pickable,
+    mut selection: ResMut<Selection>,
+) {
+    // the left button shoots in walk mode
+    if walker.enabled || !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Some(cursor) = windows.get_single().ok().and_then(|window| window.cursor_position()) else {
+        return;
+    };
+
+    let Some((camera, camera_transform)) = cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .max_by_key(|(camera, _)| camera.order) else {
+        return;
+    };
+
+    let Some(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+
+    let hit = surfaces
+        .iter()
+        .filter_map(|(mesh, transform, wall, flat, thing)| {
+            let distance = ray_mesh(&ray, meshes.get(mesh)?, transform)?;
+            Some((distance, picked_of(wall, flat, thing)?))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    selection.picked = hit.map(|(_, picked)| picked);
+}
+
+/// tint every entity of the selected element, again after movers rebuild them
+fn highlight(
+    mut commands: Commands,
+    mut selection: ResMut<Selection>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    surfaces: Highlightable,
+) {
+    let stale = selection
+        .highlighted
+        .iter()
+        .any(|(entity, _)| !surfaces.contains(*entity));
+
+    if !selection.is_changed() && !stale {
+        return;
+    }
+
+    let selection = selection.bypass_change_detection();
+
+    for (entity, material) in selection.highlighted.drain(..) {
+        if surfaces.contains(entity) {
+            commands.entity(entity).insert(material);
+        }
+    }
+
+    let Some(picked) = selection.picked else {
+        return;
+    };
+
+    for (entity, material, wall, flat, thing) in surfaces.iter() {
+        if picked_of(wall, flat, thing) != Some(picked) {
+            continue;
+        }
+
+        let Some(mut tinted) = materials.get(material).cloned() else {
+            continue;
+        };
+
+        tinted.base_color = HIGHLIGHT_COLOR;
+
+        commands.entity(entity).insert(materials.add(tinted));
+        selection.highlighted.push((entity, material.clone()));
+    }
+}
+
+fn update_panel(
+    selection: Res<Selection>,
+    manager: Res<MapManager>,
+    mut panels: Query<&mut Style, With<InspectorPanel>>,
+    mut texts: Query<&mut Text, With<InspectorText>>,
+) {
+    if !selection.is_changed() {
+        return;
+    }
+
+    for mut style in panels.iter_mut() {
+        style.display = if selection.picked.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    if let Some(picked) = selection.picked {
+        for mut text in texts.iter_mut() {
+            text.sections[0].value = selection_text(&manager.map, picked);
+        }
+    }
+}
+
+fn cleanup_inspector(mut commands: Commands, panels: Query<Entity, With<InspectorPanel>>) {
+    for entity in panels.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.remove_resource::<Selection>();
+}
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_inspector.in_schedule(OnEnter(GameState::MapView)))
+            .add_system(cleanup_inspector.in_schedule(OnExit(GameState::MapView)))
+            .add_systems(
+                (clear_selection, pick, highlight, update_panel)
+                    .chain()
+                    .distributive_run_if(resource_exists::<Selection>())
+                    .distributive_run_if(resource_exists::<Walker>())
+                    .in_set(OnUpdate(GameState::MapView)),
+            );
+    }
+}
@@ -0,0 +1,295 @@
+use bevy::prelude::*;
+
+use crate::mapmanager::complete_map::CompleteMap;
+use crate::state::GameState;
+use crate::tic::TicClock;
+
+const GLOWSPEED: i16 = 8;
+const STROBEBRIGHT: i16 = 5;
+const FASTDARK: i16 = 15;
+const SLOWDARK: i16 = 35;
+
+/// Marks a surface whose brightness follows the light level of `sector`
+#[derive(Component)]
+pub struct LitSurface {
+    pub sector: usize,
+}
+
+#[derive(Clone)]
+enum LightThinker {
+    Flash {
+        count: i16,
+        max_light: i16,
+        min_light: i16,
+        max_time: i16,
+        min_time: i16,
+    },
+    Strobe {
+        count: i16,
+        min_light: i16,
+        max_light: i16,
+        dark_time: i16,
+        bright_time: i16,
+    },
+    Glow {
+        min_light: i16,
+        max_light: i16,
+        direction: i16,
+    },
+    FireFlicker {
+        count: i16,
+        max_light: i16,
+        min_light: i16,
+    },
+}
+
+struct SectorLight {
+    base: i16,
+    level: i16,
+    thinker: Option<LightThinker>,
+    dirty: bool,
+}
+
+#[derive(Resource)]
+pub struct SectorLights {
+    sectors: Vec<SectorLight>,
+    rng: u32,
+    pub frozen: bool,
+}
+
+impl SectorLights {
+    pub fn new(map: &CompleteMap) -> Self {
+        let mut lights = SectorLights {
+            sectors: Vec::new(),
+            rng: 0x2545_f491,
+            frozen: false,
+        };
+
+        for (i, sector) in map.sector_vec.iter().enumerate() {
+            let level = sector.light_level;
+
+            let min_light = map
+                .sector_neighbours(i)
+                .iter()
+                .map(|&n| map.sector_vec[n].light_level)
+                .fold(level, i16::min);
+
+            let thinker = match sector.special {
+                1 => Some(LightThinker::Flash {
+                    count: (lights.random() & 64) + 1,
+                    max_light: level,
+                    min_light,
+                    max_time: 64,
+                    min_time: 7,
+                }),
+                2 | 4 => Some(lights.strobe(level, min_light, FASTDARK, false)),
+                3 => Some(lights.strobe(level, min_light, SLOWDARK, false)),
+                8 => Some(LightThinker::Glow {
+                    min_light,
+                    max_light: level,
+                    direction: -1,
+                }),
+                12 => Some(lights.strobe(level, min_light, SLOWDARK, true)),
+                13 => Some(lights.strobe(level, min_light, FASTDARK, true)),
+                17 => Some(LightThinker::FireFlicker {
+                    count: 4,
+                    max_light: level,
+                    min_light: min_light + 16,
+                }),
+                _ => None,
+            };
+
+            lights.sectors.push(SectorLight {
+                base: level,
+                level,
+                thinker,
+                dirty: true,
+            });
+        }
+
+        lights
+    }
+
+    fn strobe(&mut self, level: i16, min_light: i16, dark_time: i16, in_sync: bool) -> LightThinker {
+        LightThinker::Strobe {
+            count: if in_sync { 1 } else { (self.random() & 7) + 1 },
+            min_light: if min_light == level { 0 } else { min_light },
+            max_light: level,
+            dark_time,
+            bright_time: STROBEBRIGHT,
+        }
+    }
+
+    fn random(&mut self) -> i16 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        (self.rng & 0xff) as i16
+    }
+
+    /// current light level of a sector, 0-255
+    pub fn level(&self, sector: usize) -> i16 {
+        self.sectors.get(sector).map_or(255, |s| s.level)
+    }
+
+    fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+
+        for sector in self.sectors.iter_mut() {
+            if frozen && sector.level != sector.base {
+                sector.level = sector.base;
+                sector.dirty = true;
+            }
+        }
+    }
+
+    fn tick(&mut self) {
+        for i in 0..self.sectors.len() {
+            let Some(mut thinker) = self.sectors[i].thinker.clone() else {
+                continue;
+            };
+
+            let mut level = self.sectors[i].level;
+
+            match &mut thinker {
+                LightThinker::Flash {
+                    count,
+                    max_light,
+                    min_light,
+                    max_time,
+                    min_time,
+                } => {
+                    *count -= 1;
+                    if *count <= 0 {
+                        if level == *max_light {
+                            level = *min_light;
+                            *count = (self.random() & *min_time) + 1;
+                        } else {
+                            level = *max_light;
+                            *count = (self.random() & *max_time) + 1;
+                        }
+                    }
+                }
+                LightThinker::Strobe {
+                    count,
+                    min_light,
+                    max_light,
+                    dark_time,
+                    bright_time,
+                } => {
+                    *count -= 1;
+                    if *count <= 0 {
+                        if level == *min_light {
+                            level = *max_light;
+                            *count = *bright_time;
+                        } else {
+                            level = *min_light;
+                            *count = *dark_time;
+                        }
+                    }
+                }
+                LightThinker::Glow {
+                    min_light,
+                    max_light,
+                    direction,
+                } => {
+                    if *direction < 0 {
+                        level -= GLOWSPEED;
+                        if level <= *min_light {
+                            level += GLOWSPEED;
+                            *direction = 1;
+                        }
+                    } else {
+                        level += GLOWSPEED;
+                        if level >= *max_light {
+                            level -= GLOWSPEED;
+                            *direction = -1;
+                        }
+                    }
+                }
+                LightThinker::FireFlicker {
+                    count,
+                    max_light,
+                    min_light,
+                } => {
+                    *count -= 1;
+                    if *count <= 0 {
+                        let amount = (self.random() & 3) * 16;
+                        if level - amount < *min_light {
+                            level = *min_light;
+                        } else {
+                            level = *max_light - amount;
+                        }
+                        *count = 4;
+                    }
+                }
+            }
+
+            let sector = &mut self.sectors[i];
+            sector.thinker = Some(thinker);
+            if sector.level != level {
+                sector.level = level;
+                sector.dirty = true;
+            }
+        }
+    }
+}
+
+fn light_color(level: i16) -> [f32; 4] {
+    let l = level.clamp(0, 255) as f32 / 255.;
+    Color::rgb(l, l, l).as_linear_rgba_f32()
+}
+
+fn toggle_freeze(keys: Res<Input<KeyCode>>, mut lights: ResMut<SectorLights>) {
+    if keys.just_pressed(KeyCode::L) {
+        let frozen = !lights.frozen;
+        lights.set_frozen(frozen);
+        info!("Sector lighting {}", if frozen { "frozen" } else { "animated" });
+    }
+}
+
+fn run_light_thinkers(clock: Res<TicClock>, mut lights: ResMut<SectorLights>) {
+    if lights.frozen {
+        return;
+    }
+
+    for _ in 0..clock.tics {
+        lights.tick();
+    }
+}
+
+fn apply_sector_lights(
+    mut lights: ResMut<SectorLights>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    surfaces: Query<(Ref<LitSurface>, &Handle<Mesh>)>,
+) {
+    for (surface, handle) in surfaces.iter() {
+        let dirty = lights.sectors.get(surface.sector).map_or(false, |s| s.dirty);
+
+        if !dirty && !surface.is_added() {
+            continue;
+        }
+
+        if let Some(mesh) = meshes.get_mut(handle) {
+            let color = light_color(lights.level(surface.sector));
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![color; mesh.count_vertices()]);
+        }
+    }
+
+    for sector in lights.sectors.iter_mut() {
+        sector.dirty = false;
+    }
+}
+
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (toggle_freeze, run_light_thinkers, apply_sector_lights)
+                .chain()
+                .distributive_run_if(resource_exists::<SectorLights>())
+                .in_set(OnUpdate(GameState::MapView)),
+        );
+    }
+}
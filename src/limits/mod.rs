@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::export::{load_map, map_args};
+use crate::mapmanager::complete_map::{trimmed, CompleteMap};
+use crate::mapmanager::MapManager;
+use crate::sky::is_sky_flat;
+
+/// element indices are signed 16 bit numbers in the map lumps
+const MAX_INDEX: usize = 32767;
+/// BLOCKMAP offsets are signed 16 bit word counts
+const MAX_BLOCKMAP: usize = 65536;
+/// MAXVISPLANES, MAXDRAWSEGS and MAXOPENINGS of the vanilla renderer
+const MAX_VISPLANES: usize = 128;
+const MAX_DRAWSEGS: usize = 256;
+const MAX_OPENINGS: usize = SCREEN_WIDTH * 64;
+
+const SCREEN_WIDTH: usize = 320;
+const SCREEN_HEIGHT: f32 = 200.;
+/// distance to the projection plane for a 90 degree field of view
+const PROJECTION: f32 = SCREEN_WIDTH as f32 / 2.;
+/// VIEWHEIGHT, the eye above the floor
+const VIEW_HEIGHT: f32 = 41.;
+/// segments closer than this are clipped away
+const NEAR: f32 = 1.;
+
+/// side of a BLOCKMAP cell
+const MAP_BLOCK: i32 = 128;
+/// sectors looked around from besides the player starts
+const SAMPLED_SECTORS: usize = 256;
+
+/// player 1 to 4 and deathmatch starts
+const STARTS: [i16; 5] = [1, 2, 3, 4, 11];
+
+/// one static limit of the vanilla engine and how close the map comes to it
+pub struct Limit {
+    pub name: &'static str,
+    /// `None` when the lump it is read from is missing
+    pub value: Option<usize>,
+    pub max: usize,
+    /// viewpoint and angle the value was measured from, the whole map otherwise
+    pub view: Option<(Vec2, f32)>,
+}
+
+impl Limit {
+    pub fn exceeded(&self) -> bool {
+        self.value.is_some_and(|value| value > self.max)
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.value.map_or("-".to_string(), |value| value.to_string());
+        let status = match self.value {
+            None => "unknown",
+            Some(_) if self.exceeded() => "EXCEEDED",
+            Some(_) => "ok",
+        };
+
+        write!(f, "{:<12}{:>8} / {:<7}{}", self.name, value, self.max, status)?;
+
+        if let Some((position, angle)) = self.view {
+            write!(
+                f,
+                "  at {}, {} facing {}",
+                position.x,
+                position.y,
+                angle.to_degrees().rem_euclid(360.).round()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// what the renderer allocates for one frame
+#[derive(Clone, Copy, Default)]
+struct Frame {
+    visplanes: usize,
+    drawsegs: usize,
+    openings: usize,
+}
+
+/// floor or ceiling plane as R_FindPlane tells them apart
+type PlaneKey = (i16, [u8; 8], i16);
+
+fn plane_key(height: i16, pic: &[u8; 8], light: i16) -> PlaneKey {
+    // every sky shares one plane
+    if is_sky_flat(pic) {
+        (0, *pic, 0)
+    } else {
+        (height, *pic, light)
+    }
+}
+
+/// size in bytes of the BLOCKMAP a node builder would write
+fn blockmap_size(map: &CompleteMap) -> usize {
+    if map.vert_vec.is_empty() {
+        return 0;
+    }
+
+    let (min, max) = map.vert_vec.iter().fold(
+        (IVec2::splat(i32::MAX), IVec2::splat(i32::MIN)),
+        |(min, max), vert| {
+            let point = IVec2::new(vert.x as i32, vert.y as i32);
+            (min.min(point), max.max(point))
+        },
+    );
+
+    let cells = (max - min) / MAP_BLOCK + 1;
+    let mut lines = vec![0usize; (cells.x * cells.y) as usize];
+
+    // lines missing a vertex are left for the lint to report
+    for (a, b) in (0..map.linedef_vec.len()).filter_map(|line| map.line_ends(line)) {
+        let side = |p: Vec2| (b - a).perp_dot(p - a).signum();
+
+        let first = (a.min(b).as_ivec2() - min) / MAP_BLOCK;
+        let last = (a.max(b).as_ivec2() - min) / MAP_BLOCK;
+
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                let low = (IVec2::new(x, y) * MAP_BLOCK + min).as_vec2();
+                let high = low + MAP_BLOCK as f32;
+                let corners = [low, high, Vec2::new(low.x, high.y), Vec2::new(high.x, low.y)].map(side);
+
+                // the line goes through the cell unless all corners are on one side
+                if corners.iter().any(|&corner| corner != corners[0]) || corners[0] == 0. {
+                    lines[(y * cells.x + x) as usize] += 1;
+                }
+            }
+        }
+    }
+
+    // header, an offset per cell and every list starts with 0 and ends with -1
+    8 + 2 * lines.len() + lines.iter().map(|count| 2 * (count + 2)).sum::<usize>()
+}
+
+/// player starts looking their way and a spot in sampled sectors looking
+/// four ways around
+fn viewpoints(map: &CompleteMap) -> Vec<(Vec2, f32)> {
+    let mut views: Vec<(Vec2, f32)> = map
+        .things_vec
+        .iter()
+        .filter(|thing| STARTS.contains(&thing.thing_type))
+        .map(|thing| (Vec2::new(thing.x as f32, thing.y as f32), (thing.angle as f32).to_radians()))
+        .collect();
+
+    let step = (map.sector_vec.len() / SAMPLED_SECTORS).max(1);
+
+    for sector in (0..map.sector_vec.len()).step_by(step) {
+        // step inside from the first line on its outline
+        let spot = (0..map.linedef_vec.len()).find_map(|line| {
+            let (a, b) = map.line_ends(line)?;
+            let right = (b - a).perp().normalize_or_zero() * -16.;
+
+            match map.line_sectors(line) {
+                (Some(front), _) if front == sector => Some((a + b) / 2. + right),
+                (_, Some(back)) if back == sector => Some((a + b) / 2. - right),
+                _ => None,
+            }
+        });
+
+        let Some(spot) = spot.filter(|&spot| map.sector_at(spot) == Some(sector)) else {
+            continue;
+        };
+
+        for quarter in 0..4 {
+            views.push((spot, quarter as f32 * std::f32::consts::FRAC_PI_2));
+        }
+    }
+
+    views
+}
+
+/// casts a ray per screen column and counts visplanes, drawsegs and openings
+/// the way the vanilla renderer would allocate them
+fn render_frame(map: &CompleteMap, position: Vec2, angle: f32) -> Option<Frame> {
+    let eye = map.sector_vec[map.sector_at(position)?].floor_height as f32 + VIEW_HEIGHT;
+    let forward = Vec2::new(angle.cos(), angle.sin());
+    let right = Vec2::new(angle.sin(), -angle.cos());
+
+    // lines crossing each column with their depth
+    let mut columns: Vec<Vec<(f32, usize)>> = vec![Vec::new(); SCREEN_WIDTH];
+
+    for i in 0..map.linedef_vec.len() {
+        let Some((a, b)) = map.line_ends(i) else {
+            continue;
+        };
+        let (mut near_a, mut near_b) = (a - position, b - position);
+        let (depth_a, depth_b) = (near_a.dot(forward), near_b.dot(forward));
+
+        if depth_a < NEAR && depth_b < NEAR {
+            continue;
+        }
+
+        if depth_a < NEAR {
+            near_a = near_a.lerp(near_b, (NEAR - depth_a) / (depth_b - depth_a));
+        } else if depth_b < NEAR {
+            near_b = near_b.lerp(near_a, (NEAR - depth_b) / (depth_a - depth_b));
+        }
+
+        let screen = |p: Vec2| PROJECTION + p.dot(right) / p.dot(forward) * PROJECTION;
+        let (x1, x2) = (screen(near_a), screen(near_b));
+        let first = (x1.min(x2) - 0.5).ceil().max(0.) as usize;
+        let last = (x1.max(x2) - 0.5).floor().min(SCREEN_WIDTH as f32 - 1.);
+
+        if last < 0. {
+            continue;
+        }
+
+        let edge = b - a;
+
+        for (column, hits) in columns.iter_mut().enumerate().take(last as usize + 1).skip(first) {
+            let direction = forward + right * ((column as f32 + 0.5 - PROJECTION) / PROJECTION);
+            let denominator = direction.perp_dot(edge);
+
+            if denominator.abs() < f32::EPSILON {
+                continue;
+            }
+
+            let depth = (a - position).perp_dot(edge) / denominator;
+            let along = (a - position).perp_dot(direction) / denominator;
+
+            if depth >= NEAR && (0. ..=1.).contains(&along) {
+                hits.push((depth, i));
+            }
+        }
+    }
+
+    let row = |height: f32, depth: f32| SCREEN_HEIGHT / 2. - (height - eye) * PROJECTION / depth;
+
+    let mut frame = Frame::default();
+    let mut planes: HashMap<PlaneKey, usize> = HashMap::new();
+    // column after the last one each line was seen in, to count drawseg ranges
+    let mut next_column = vec![usize::MAX; map.linedef_vec.len()];
+
+    for (column, hits) in columns.iter_mut().enumerate() {
+        hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let (mut top, mut bottom) = (0., SCREEN_HEIGHT);
+        let mut marked: HashMap<PlaneKey, usize> = HashMap::new();
+        let (mut last_floor, mut last_ceiling) = (None, None);
+
+        for &(depth, line) in hits.iter() {
+            let linedef = &map.linedef_vec[line];
+            let (front, back) = map.line_sectors(line);
+            let facing_front = map.point_on_front(line, position);
+            let (near, far, sidedef) = if facing_front {
+                (front, back, linedef.front_sidedef)
+            } else {
+                (back, front, linedef.back_sidedef)
+            };
+
+            // the back of a one-sided line is not drawn
+            let Some(near) = near else {
+                continue;
+            };
+            let near = &map.sector_vec[near];
+
+            // planes of the near sector reaching up to this line
+            let ceiling = plane_key(near.ceil_height, &near.ceil_tex, near.light_level);
+            if near.ceil_height as f32 > eye && row(near.ceil_height as f32, depth) > top && last_ceiling != Some(ceiling) {
+                *marked.entry(ceiling).or_default() += 1;
+                last_ceiling = Some(ceiling);
+            }
+
+            let floor = plane_key(near.floor_height, &near.floor_tex, near.light_level);
+            if (near.floor_height as f32) < eye && row(near.floor_height as f32, depth) < bottom && last_floor != Some(floor) {
+                *marked.entry(floor).or_default() += 1;
+                last_floor = Some(floor);
+            }
+
+            if next_column[line] != column {
+                frame.drawsegs += 1;
+            }
+            next_column[line] = column + 1;
+
+            let Some(far) = far else {
+                break;
+            };
+            let far = &map.sector_vec[far];
+
+            // clip arrays kept for sprites and masked middle textures
+            let middle = map.sidefef_vec.get(sidedef as usize).map(|side| trimmed(&side.mid_tex));
+            let masked = middle.is_some_and(|middle| !middle.is_empty() && middle != "-");
+            frame.openings += [far.ceil_height < near.ceil_height, far.floor_height > near.floor_height, masked]
+                .iter()
+                .filter(|&&silhouette| silhouette)
+                .count();
+
+            top = f32::max(top, row(near.ceil_height.min(far.ceil_height) as f32, depth));
+            bottom = f32::min(bottom, row(near.floor_height.max(far.floor_height) as f32, depth));
+
+            if top >= bottom {
+                break;
+            }
+        }
+
+        // a plane marked again in the same column needs a visplane of its own
+        for (key, count) in marked {
+            let planes = planes.entry(key).or_default();
+            *planes = (*planes).max(count);
+        }
+    }
+
+    frame.visplanes = planes.values().sum();
+    Some(frame)
+}
+
+/// lump element count, `None` when the map has no such lump
+fn lump_count(manager: &MapManager, name: &str, size: usize) -> Option<usize> {
+    manager
+        .map_lump(name)
+        .map(|lump| lump.metadata.size as usize / size)
+}
+
+/// static limits of the vanilla engine for the loaded map
+pub fn check_limits(manager: &MapManager) -> Vec<Limit> {
+    let map = &manager.map;
+    let whole = |name, value, max| Limit {
+        name,
+        value,
+        max,
+        view: None,
+    };
+
+    let blockmap = manager
+        .map_lump("BLOCKMAP")
+        .map_or_else(|| blockmap_size(map), |lump| lump.metadata.size as usize);
+
+    let mut limits = vec![
+        whole("Vertices", Some(map.vert_vec.len()), MAX_INDEX),
+        whole("Linedefs", Some(map.linedef_vec.len()), MAX_INDEX),
+        whole("Sidedefs", Some(map.sidefef_vec.len()), MAX_INDEX),
+        whole("Sectors", Some(map.sector_vec.len()), MAX_INDEX),
+        whole("Segs", lump_count(manager, "SEGS", 12), MAX_INDEX),
+        whole("Subsectors", lump_count(manager, "SSECTORS", 4), MAX_INDEX),
+        whole("Nodes", lump_count(manager, "NODES", 28), MAX_INDEX),
+        whole("Blockmap", Some(blockmap), MAX_BLOCKMAP),
+    ];
+
+    // the worst frame of every sampled viewpoint
+    let mut worst: [Option<(usize, Vec2, f32)>; 3] = [None; 3];
+
+    for (position, angle) in viewpoints(map) {
+        let Some(frame) = render_frame(map, position, angle) else {
+            continue;
+        };
+
+        for (worst, value) in worst.iter_mut().zip([frame.visplanes, frame.drawsegs, frame.openings]) {
+            if worst.is_none_or(|(max, _, _)| value > max) {
+                *worst = Some((value, position, angle));
+            }
+        }
+    }
+
+    for (name, max, worst) in [
+        ("Visplanes", MAX_VISPLANES, worst[0]),
+        ("Drawsegs", MAX_DRAWSEGS, worst[1]),
+        ("Openings", MAX_OPENINGS, worst[2]),
+    ] {
+        limits.push(Limit {
+            name,
+            value: worst.map(|(value, _, _)| value),
+            max,
+            view: worst.map(|(_, position, angle)| (position, angle)),
+        });
+    }
+
+    limits
+}
+
+const USAGE: &str = "usage: limits --iwad <wad> [--pwad <wad>] [--map <name|index>]";
+
+/// `limits` command line entry, reports the vanilla limits of one map or every
+/// map of the PWAD and fails when any of them is exceeded
+pub fn run(args: &[String]) -> Result<(), String> {
+    let (iwad, pwad, maps) = map_args(args, USAGE)?;
+    let mut exceeded = 0;
+
+    for map in maps {
+        let manager = load_map(&iwad, &pwad, &map)?;
+        let limits = check_limits(&manager);
+
+        println!("{}:", manager.map_name);
+
+        for limit in &limits {
+            println!("  {}", limit);
+        }
+
+        exceeded += limits.iter().filter(|limit| limit.exceeded()).count();
+    }
+
+    if exceeded > 0 {
+        return Err(format!("{} limits exceeded", exceeded));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,475 @@
+mod vanilla;
+mod viewer;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::export::{load_map, map_args};
+use crate::mapmanager::complete_map::{trimmed, CompleteMap};
+use crate::mapmanager::MapManager;
+use crate::sky::is_sky_flat;
+use crate::things::{is_actor, thing_radius};
+
+pub use viewer::LintPlugin;
+
+/// ML_BLOCKING, ML_BLOCKMONSTERS and ML_TWOSIDED linedef flags
+const ML_BLOCKING: i16 = 1;
+const ML_BLOCKMONSTERS: i16 = 2;
+const ML_TWOSIDED: i16 = 4;
+
+/// size of the cells linedefs are bucketed in for the crossing check
+const CELL_SIZE: i32 = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Map element a diagnostic is about, by its index in the map lumps
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Element {
+    Map,
+    Vertex(usize),
+    Linedef(usize),
+    Sidedef(usize),
+    Sector(usize),
+    Thing(usize),
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Element::Map => write!(f, "Map"),
+            Element::Vertex(i) => write!(f, "Vertex {}", i),
+            Element::Linedef(i) => write!(f, "Linedef {}", i),
+            Element::Sidedef(i) => write!(f, "Sidedef {}", i),
+            Element::Sector(i) => write!(f, "Sector {}", i),
+            Element::Thing(i) => write!(f, "Thing {}", i),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub element: Element,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}: {}", self.severity, self.element, self.message)
+    }
+}
+
+fn error(element: Element, message: String) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        element,
+        message,
+    }
+}
+
+fn warning(element: Element, message: String) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        element,
+        message,
+    }
+}
+
+fn vert(map: &CompleteMap, index: i16) -> IVec2 {
+    let vert = &map.vert_vec[index as usize];
+    IVec2::new(vert.x as i32, vert.y as i32)
+}
+
+/// indices into other lumps, everything else relies on them being valid
+fn check_references(map: &CompleteMap, out: &mut Vec<Diagnostic>) -> bool {
+    let errors = out.len();
+    let verts = map.vert_vec.len() as i16;
+    let sides = map.sidefef_vec.len() as i16;
+    let sectors = map.sector_vec.len() as i16;
+
+    for (i, line) in map.linedef_vec.iter().enumerate() {
+        for vertex in [line.start_vert, line.end_vert] {
+            if !(0..verts).contains(&vertex) {
+                out.push(error(Element::Linedef(i), format!("references missing vertex {}", vertex)));
+            }
+        }
+
+        if line.front_sidedef < 0 {
+            out.push(error(Element::Linedef(i), "has no front sidedef".to_string()));
+        }
+
+        for side in [line.front_sidedef, line.back_sidedef] {
+            if side >= sides {
+                out.push(error(Element::Linedef(i), format!("references missing sidedef {}", side)));
+            }
+        }
+    }
+
+    for (i, side) in map.sidefef_vec.iter().enumerate() {
+        if !(0..sectors).contains(&side.sector) {
+            out.push(error(Element::Sidedef(i), format!("references missing sector {}", side.sector)));
+        }
+    }
+
+    out.len() == errors
+}
+
+fn check_lines(map: &CompleteMap, out: &mut Vec<Diagnostic>) {
+    for (i, line) in map.linedef_vec.iter().enumerate() {
+        if vert(map, line.start_vert) == vert(map, line.end_vert) {
+            out.push(error(Element::Linedef(i), "has zero length".to_string()));
+        }
+
+        let two_sided = line.back_sidedef >= 0;
+
+        if two_sided && line.flags & ML_TWOSIDED == 0 {
+            out.push(warning(
+                Element::Linedef(i),
+                "has a back sidedef but not the two-sided flag".to_string(),
+            ));
+        } else if !two_sided && line.flags & ML_TWOSIDED != 0 {
+            out.push(warning(
+                Element::Linedef(i),
+                "has the two-sided flag but no back sidedef".to_string(),
+            ));
+        }
+    }
+}
+
+fn check_unused(map: &CompleteMap, out: &mut Vec<Diagnostic>) {
+    let mut used_verts = vec![false; map.vert_vec.len()];
+    let mut used_sectors = vec![false; map.sector_vec.len()];
+
+    for line in &map.linedef_vec {
+        used_verts[line.start_vert as usize] = true;
+        used_verts[line.end_vert as usize] = true;
+    }
+
+    for side in &map.sidefef_vec {
+        used_sectors[side.sector as usize] = true;
+    }
+
+    for (i, _) in used_verts.iter().enumerate().filter(|(_, used)| !**used) {
+        out.push(warning(Element::Vertex(i), "is not used by any linedef".to_string()));
+    }
+
+    for (i, _) in used_sectors.iter().enumerate().filter(|(_, used)| !**used) {
+        out.push(warning(Element::Sector(i), "is not used by any sidedef".to_string()));
+    }
+}
+
+/// every vertex on the outline of a closed sector joins an even number of its lines
+fn check_closed(map: &CompleteMap, out: &mut Vec<Diagnostic>) {
+    let mut degrees: HashMap<(usize, i16), usize> = HashMap::new();
+
+    for i in 0..map.linedef_vec.len() {
+        let line = &map.linedef_vec[i];
+
+        let sectors = match map.line_sectors(i) {
+            (Some(front), Some(back)) if front == back => continue,
+            (front, back) => [front, back],
+        };
+
+        for sector in sectors.into_iter().flatten() {
+            *degrees.entry((sector, line.start_vert)).or_default() += 1;
+            *degrees.entry((sector, line.end_vert)).or_default() += 1;
+        }
+    }
+
+    let mut open: Vec<(usize, i16)> = degrees
+        .into_iter()
+        .filter(|(_, degree)| degree % 2 != 0)
+        .map(|(key, _)| key)
+        .collect();
+
+    open.sort();
+    open.dedup_by_key(|(sector, _)| *sector);
+
+    for (sector, vertex) in open {
+        out.push(error(Element::Sector(sector), format!("is not closed at vertex {}", vertex)));
+    }
+}
+
+/// overlapping and crossing linedefs, bucketed by cell to skip far apart pairs
+fn check_crossings(map: &CompleteMap, out: &mut Vec<Diagnostic>) {
+    let mut cells: HashMap<IVec2, Vec<usize>> = HashMap::new();
+
+    for (i, line) in map.linedef_vec.iter().enumerate() {
+        let (a, b) = (vert(map, line.start_vert), vert(map, line.end_vert));
+
+        if a == b {
+            continue;
+        }
+
+        let (min, max) = (a.min(b), a.max(b));
+
+        for x in min.x.div_euclid(CELL_SIZE)..=max.x.div_euclid(CELL_SIZE) {
+            for y in min.y.div_euclid(CELL_SIZE)..=max.y.div_euclid(CELL_SIZE) {
+                cells.entry(IVec2::new(x, y)).or_default().push(i);
+            }
+        }
+    }
+
+    let mut pairs: HashSet<(usize, usize)> = HashSet::new();
+
+    for lines in cells.values() {
+        for (n, &i) in lines.iter().enumerate() {
+            for &j in &lines[n + 1..] {
+                pairs.insert((i.min(j), i.max(j)));
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+    pairs.sort();
+
+    let cross = |o: IVec2, a: IVec2, b: IVec2| {
+        let (a, b) = (a - o, b - o);
+        (a.x as i64 * b.y as i64 - a.y as i64 * b.x as i64).signum()
+    };
+
+    for (i, j) in pairs {
+        let (a, b) = (vert(map, map.linedef_vec[i].start_vert), vert(map, map.linedef_vec[i].end_vert));
+        let (c, d) = (vert(map, map.linedef_vec[j].start_vert), vert(map, map.linedef_vec[j].end_vert));
+
+        let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+        let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+
+        if d1 == 0 && d2 == 0 {
+            // collinear, compare along the longer axis
+            let axis = if (b - a).x.abs() >= (b - a).y.abs() { 0 } else { 1 };
+            let (lo1, hi1) = (a[axis].min(b[axis]), a[axis].max(b[axis]));
+            let (lo2, hi2) = (c[axis].min(d[axis]), c[axis].max(d[axis]));
+
+            if lo1.max(lo2) < hi1.min(hi2) {
+                out.push(error(Element::Linedef(i), format!("overlaps linedef {}", j)));
+            }
+        } else if d1 * d2 < 0 && d3 * d4 < 0 {
+            out.push(error(Element::Linedef(i), format!("crosses linedef {}", j)));
+        }
+    }
+}
+
+/// one texture slot of a sidedef and the wall it covers
+struct Section {
+    sidedef: usize,
+    part: &'static str,
+    texture: String,
+    /// height of the wall, the opening for middle textures of two-sided lines
+    height: i32,
+    /// the renderer draws this part, an empty name leaves a hall of mirrors
+    needed: bool,
+    /// middle texture of a two-sided line, drawn masked and not tiled vertically
+    masked: bool,
+}
+
+/// texture slots of every sidedef, shared sidedefs only once
+fn sections(map: &CompleteMap) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    // sidedefs can be shared by several lines, report them once
+    let mut checked: HashSet<i16> = HashSet::new();
+
+    for (i, line) in map.linedef_vec.iter().enumerate() {
+        let (front, back) = map.line_sectors(i);
+
+        for (side_index, this, other) in [
+            (line.front_sidedef, front, back),
+            (line.back_sidedef, back, front),
+        ] {
+            let Some(this) = this else {
+                continue;
+            };
+
+            if !checked.insert(side_index) {
+                continue;
+            }
+
+            let side = &map.sidefef_vec[side_index as usize];
+            let this = &map.sector_vec[this];
+            let other = other.map(|other| &map.sector_vec[other]);
+            let (floor, ceil) = (this.floor_height as i32, this.ceil_height as i32);
+
+            let parts = match other {
+                None => [(0, false), (ceil - floor, true), (0, false)],
+                Some(other) => {
+                    let (other_floor, other_ceil) = (other.floor_height as i32, other.ceil_height as i32);
+                    let sky = is_sky_flat(&this.ceil_tex) && is_sky_flat(&other.ceil_tex);
+
+                    [
+                        (ceil - other_ceil, other_ceil < ceil && !sky),
+                        (ceil.min(other_ceil) - floor.max(other_floor), false),
+                        (other_floor - floor, other_floor > floor),
+                    ]
+                }
+            };
+
+            for ((height, needed), (part, name)) in parts.into_iter().zip([
+                ("upper", &side.upper_tex),
+                ("middle", &side.mid_tex),
+                ("lower", &side.lower_tex),
+            ]) {
+                sections.push(Section {
+                    sidedef: side_index as usize,
+                    part,
+                    texture: trimmed(name).to_uppercase(),
+                    height,
+                    needed,
+                    masked: other.is_some() && part == "middle",
+                });
+            }
+        }
+    }
+
+    sections
+}
+
+/// names of the textures a sidedef needs given the sectors on both sides
+fn check_textures(manager: &MapManager, out: &mut Vec<Diagnostic>) {
+    let map = &manager.map;
+
+    for section in sections(map) {
+        let element = Element::Sidedef(section.sidedef);
+
+        if section.texture.is_empty() || section.texture == "-" {
+            if section.needed {
+                out.push(warning(
+                    element,
+                    format!("is missing its {} texture, vanilla shows a hall of mirrors there", section.part),
+                ));
+            }
+        } else if !manager.is_texture(&section.texture) {
+            out.push(error(element, format!("has unknown {} texture {}", section.part, section.texture)));
+        }
+    }
+
+    for (i, sector) in map.sector_vec.iter().enumerate() {
+        for (part, name) in [("floor", &sector.floor_tex), ("ceiling", &sector.ceil_tex)] {
+            let name = trimmed(name).to_uppercase();
+
+            if !manager.is_flat(&name) {
+                out.push(error(Element::Sector(i), format!("has unknown {} flat {}", part, name)));
+            }
+        }
+    }
+}
+
+/// the square blocking box of a thing reaches over the line (P_BoxOnLineSide)
+fn box_on_line(center: Vec2, radius: f32, a: Vec2, b: Vec2) -> bool {
+    let (min, max) = (center - radius, center + radius);
+
+    if max.x <= a.x.min(b.x) || min.x >= a.x.max(b.x) || max.y <= a.y.min(b.y) || min.y >= a.y.max(b.y) {
+        return false;
+    }
+
+    let side = |p: Vec2| (b - a).perp_dot(p - a) > 0.;
+    let corners = [min, max, Vec2::new(min.x, max.y), Vec2::new(max.x, min.y)];
+
+    corners.iter().any(|&c| side(c)) && corners.iter().any(|&c| !side(c))
+}
+
+fn check_things(map: &CompleteMap, out: &mut Vec<Diagnostic>) {
+    for player in 1..=4 {
+        if map.things_vec.iter().any(|thing| thing.thing_type == player) {
+            continue;
+        }
+
+        if player == 1 {
+            out.push(error(Element::Map, "has no player 1 start".to_string()));
+        } else {
+            out.push(warning(Element::Map, format!("has no player {} start for cooperative play", player)));
+        }
+    }
+
+    for (i, thing) in map.things_vec.iter().enumerate() {
+        let position = Vec2::new(thing.x as f32, thing.y as f32);
+
+        if map.sector_at(position).is_none() {
+            out.push(error(Element::Thing(i), "is outside the map".to_string()));
+            continue;
+        }
+
+        if !is_actor(thing.thing_type) {
+            continue;
+        }
+
+        let radius = thing_radius(thing.thing_type);
+        let player = matches!(thing.thing_type, 1..=4 | 11);
+
+        for (j, line) in map.linedef_vec.iter().enumerate() {
+            let blocking = line.back_sidedef < 0
+                || line.flags & ML_BLOCKING != 0
+                || (!player && line.flags & ML_BLOCKMONSTERS != 0);
+
+            if !blocking {
+                continue;
+            }
+
+            let a = vert(map, line.start_vert).as_vec2();
+            let b = vert(map, line.end_vert).as_vec2();
+
+            if box_on_line(position, radius, a, b) {
+                out.push(error(Element::Thing(i), format!("is stuck in linedef {}", j)));
+                break;
+            }
+        }
+    }
+}
+
+/// structural problems of the loaded map, errors first
+pub fn check_map(manager: &MapManager) -> Vec<Diagnostic> {
+    let map = &manager.map;
+    let mut out: Vec<Diagnostic> = Vec::new();
+
+    // the geometric checks index with these, stop when they are broken
+    if check_references(map, &mut out) {
+        check_lines(map, &mut out);
+        check_unused(map, &mut out);
+        check_closed(map, &mut out);
+        check_crossings(map, &mut out);
+        check_textures(manager, &mut out);
+        check_things(map, &mut out);
+        vanilla::check_vanilla(manager, &mut out);
+    }
+
+    out.sort_by_key(|diagnostic| diagnostic.severity);
+    out
+}
+
+const USAGE: &str = "usage: lint --iwad <wad> [--pwad <wad>] [--map <name|index>]";
+
+/// `lint` command line entry, checks one map or every map of the PWAD
+/// and fails when any of them has errors
+pub fn run(args: &[String]) -> Result<(), String> {
+    let (iwad, pwad, maps) = map_args(args, USAGE)?;
+
+    let mut errors = 0;
+
+    for map in maps {
+        let manager = load_map(&iwad, &pwad, &map)?;
+        let diagnostics = check_map(&manager);
+
+        println!("{}: {} problems", manager.map_name, diagnostics.len());
+
+        for diagnostic in &diagnostics {
+            println!("  {}", diagnostic);
+        }
+
+        errors += diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count();
+    }
+
+    if errors > 0 {
+        return Err(format!("{} errors", errors));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use super::{sections, vert, warning, Diagnostic, Element};
+use crate::mapmanager::complete_map::{CompleteMap, TextureEntry};
+use crate::mapmanager::MapManager;
+
+/// diagonal lines at least this long are likely to be split by node lines
+const SLIME_LENGTH: f32 = 256.;
+/// distance between the integer points of a line above which split vertices
+/// are rounded off the line
+const SLIME_SPACING: f32 = 16.;
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// columns more than one patch covers, R_GenerateLookup makes them composite
+fn composite_columns(manager: &MapManager, texture: &TextureEntry) -> usize {
+    let mut patch_count = vec![0u32; texture.width.max(0) as usize];
+
+    for patch in &texture.patches {
+        let Some(width) = manager
+            .map
+            .pnames
+            .get(texture.wad_ind)
+            .and_then(|names| names.get(patch.patch as usize))
+            .and_then(|name| manager.patch_width(&name.trim_end_matches('\0').to_uppercase()))
+        else {
+            continue;
+        };
+
+        let first = (patch.origin_x as i32).clamp(0, texture.width as i32) as usize;
+        let last = (patch.origin_x as i32 + width).clamp(0, texture.width as i32) as usize;
+
+        for count in &mut patch_count[first..last.max(first)] {
+            *count += 1;
+        }
+    }
+
+    patch_count.iter().filter(|&&count| count > 1).count()
+}
+
+/// Medusa, composite middle textures on two-sided lines draw garbage
+/// because vanilla only masks single patch columns
+fn check_medusa(manager: &MapManager, textures: &HashMap<String, &TextureEntry>, out: &mut Vec<Diagnostic>) {
+    let map = &manager.map;
+
+    for section in sections(map) {
+        if !section.masked {
+            continue;
+        }
+
+        let Some(texture) = textures.get(&section.texture) else {
+            continue;
+        };
+
+        let composite = composite_columns(manager, texture);
+
+        if composite > 0 {
+            out.push(warning(
+                Element::Sidedef(section.sidedef),
+                format!(
+                    "Medusa: middle texture {} has {} columns made of several patches on a two-sided line",
+                    section.texture, composite
+                ),
+            ));
+        }
+    }
+}
+
+/// tutti-frutti, vanilla columns wrap at powers of two and read past the end
+/// of textures shorter than the wall they tile
+fn check_tutti_frutti(map: &CompleteMap, textures: &HashMap<String, &TextureEntry>, out: &mut Vec<Diagnostic>) {
+    for section in sections(map) {
+        if section.masked || !section.needed {
+            continue;
+        }
+
+        let Some(texture) = textures.get(&section.texture) else {
+            continue;
+        };
+
+        let height = texture.height as i32;
+        let element = Element::Sidedef(section.sidedef);
+
+        if height > 0 && !(height as u32).is_power_of_two() {
+            out.push(warning(
+                element,
+                format!(
+                    "tutti-frutti: {} texture {} is {} high, not a power of two",
+                    section.part, section.texture, height
+                ),
+            ));
+        } else if section.height > height {
+            out.push(warning(
+                element,
+                format!(
+                    "tutti-frutti: {} wall is {} high, taller than its texture {} ({})",
+                    section.part, section.height, section.texture, height
+                ),
+            ));
+        }
+    }
+}
+
+/// slime trails, node builders split long diagonal lines at points that get
+/// rounded to whole map units and the flats bleed through the gaps
+fn check_slime_trails(map: &CompleteMap, out: &mut Vec<Diagnostic>) {
+    for (i, line) in map.linedef_vec.iter().enumerate() {
+        let delta = vert(map, line.end_vert) - vert(map, line.start_vert);
+
+        if delta.x == 0 || delta.y == 0 {
+            continue;
+        }
+
+        let length = delta.as_vec2().length();
+        let spacing = length / gcd(delta.x, delta.y) as f32;
+
+        if length >= SLIME_LENGTH && spacing >= SLIME_SPACING {
+            out.push(warning(
+                Element::Linedef(i),
+                format!(
+                    "slime trails: diagonal line {:.0} long only passes whole units every {:.0}",
+                    length, spacing
+                ),
+            ));
+        }
+    }
+}
+
+/// patterns the vanilla renderer draws wrong, missing textures are
+/// reported with the other texture checks
+pub(super) fn check_vanilla(manager: &MapManager, out: &mut Vec<Diagnostic>) {
+    let map = &manager.map;
+    let textures: HashMap<String, &TextureEntry> = map
+        .texture_defs
+        .iter()
+        .map(|(name, texture)| (name.trim_end_matches('\0').to_uppercase(), texture))
+        .collect();
+
+    check_medusa(manager, &textures, out);
+    check_tutti_frutti(map, &textures, out);
+    check_slime_trails(map, out);
+}
@@ -0,0 +1,254 @@
+use bevy::prelude::*;
+
+use super::{check_map, Diagnostic, Element, Severity};
+use crate::inspector::{Picked, Selection};
+use crate::mapmanager::complete_map::CompleteMap;
+use crate::mapmanager::components::{Side, WallSurface};
+use crate::mapmanager::MapManager;
+use crate::panel::{cleanup_panel, panel_text_bundle, toggle_panel};
+use crate::state::GameState;
+use crate::walk::{WalkCamera, Walker};
+
+/// how far from a wall or thing the camera stands when jumping to it
+const FOCUS_DISTANCE: f32 = 96.;
+/// diagnostics listed around the current one
+const LISTED: usize = 12;
+
+#[derive(Resource)]
+struct LintResults {
+    diagnostics: Vec<Diagnostic>,
+    current: Option<usize>,
+}
+
+#[derive(Component)]
+struct LintPanel;
+
+#[derive(Component)]
+struct LintText;
+
+/// linedef and side a sidedef belongs to
+fn sidedef_line(map: &CompleteMap, sidedef: usize) -> Option<(usize, Side)> {
+    map.linedef_vec.iter().enumerate().find_map(|(i, line)| {
+        if line.front_sidedef as usize == sidedef {
+            Some((i, Side::Front))
+        } else if line.back_sidedef >= 0 && line.back_sidedef as usize == sidedef {
+            Some((i, Side::Back))
+        } else {
+            None
+        }
+    })
+}
+
+/// middle of a linedef and the unit normal out of the given side
+fn line_normal(map: &CompleteMap, line: usize, side: Side) -> Option<(Vec2, Vec2)> {
+    let (a, b) = map.line_ends(line)?;
+
+    let direction = (b - a).normalize_or_zero();
+    // the front side is on the right
+    let right = Vec2::new(direction.y, -direction.x);
+
+    let normal = match side {
+        Side::Front => right,
+        Side::Back => -right,
+    };
+
+    Some(((a + b) / 2., normal))
+}
+
+/// map position and angle to look at an element from
+fn focus(map: &CompleteMap, element: Element) -> Option<(Vec2, f32)> {
+    let facing = |target: Vec2, direction: Vec2| {
+        (target - direction * FOCUS_DISTANCE, direction.y.atan2(direction.x))
+    };
+
+    match element {
+        Element::Map => None,
+        Element::Vertex(i) => {
+            let vert = map.vert_vec.get(i)?;
+            Some(facing(Vec2::new(vert.x as f32, vert.y as f32), Vec2::X))
+        }
+        Element::Linedef(i) => {
+            let (middle, normal) = line_normal(map, i, Side::Front)?;
+            Some(facing(middle, -normal))
+        }
+        Element::Sidedef(i) => {
+            let (line, side) = sidedef_line(map, i)?;
+            let (middle, normal) = line_normal(map, line, side)?;
+            Some(facing(middle, -normal))
+        }
+        Element::Sector(i) => {
+            // step inside from the first line on its outline
+            let (line, side) = (0..map.linedef_vec.len()).find_map(|line| match map.line_sectors(line) {
+                _ if map.line_ends(line).is_none() => None,
+                (Some(front), _) if front == i => Some((line, Side::Front)),
+                (_, Some(back)) if back == i => Some((line, Side::Back)),
+                _ => None,
+            })?;
+            let (middle, normal) = line_normal(map, line, side)?;
+            Some((middle + normal * 16., normal.y.atan2(normal.x)))
+        }
+        Element::Thing(i) => {
+            let thing = map.things_vec.get(i)?;
+            let angle = (thing.angle as f32).to_radians();
+            Some(facing(
+                Vec2::new(thing.x as f32, thing.y as f32),
+                Vec2::new(angle.cos(), angle.sin()),
+            ))
+        }
+    }
+}
+
+fn panel_text(results: &LintResults) -> String {
+    let errors = results
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+
+    let mut text = format!(
+        "{} errors, {} warnings  (N next, P previous)",
+        errors,
+        results.diagnostics.len() - errors
+    );
+
+    let current = results.current.unwrap_or(0);
+    let first = current.saturating_sub(LISTED / 2);
+
+    for (i, diagnostic) in results.diagnostics.iter().enumerate().skip(first).take(LISTED) {
+        let marker = if results.current == Some(i) { ">" } else { " " };
+        text += &format!("\n{} {}", marker, diagnostic);
+    }
+
+    text
+}
+
+/// K runs the checks on the loaded map and shows what they found
+fn toggle_lint(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    manager: Res<MapManager>,
+    mut panels: Query<&mut Style, With<LintPanel>>,
+) {
+    let anchor = UiRect {
+        right: Val::Px(10.),
+        bottom: Val::Px(10.),
+        ..default()
+    };
+
+    let Some(panel) = toggle_panel(&mut commands, &keys, KeyCode::K, anchor, LintPanel, &mut panels) else {
+        return;
+    };
+
+    let results = LintResults {
+        diagnostics: check_map(&manager),
+        current: None,
+    };
+
+    commands.entity(panel).with_children(|panel| {
+        panel
+            .spawn(panel_text_bundle(&asset_server, panel_text(&results)))
+            .insert(LintText);
+    });
+
+    commands.insert_resource(results);
+}
+
+/// N and P select the next and previous diagnostic and move the camera to it
+fn jump(
+    keys: Res<Input<KeyCode>>,
+    manager: Res<MapManager>,
+    mut results: ResMut<LintResults>,
+    mut walker: ResMut<Walker>,
+    mut selection: ResMut<Selection>,
+    walls: Query<&WallSurface>,
+    mut cameras: Query<&mut Transform, With<WalkCamera>>,
+) {
+    let count = results.diagnostics.len();
+
+    if count == 0 {
+        return;
+    }
+
+    let current = if keys.just_pressed(KeyCode::N) {
+        results.current.map_or(0, |current| (current + 1) % count)
+    } else if keys.just_pressed(KeyCode::P) {
+        results.current.map_or(count - 1, |current| (current + count - 1) % count)
+    } else {
+        return;
+    };
+
+    results.current = Some(current);
+
+    let map = &manager.map;
+    let element = results.diagnostics[current].element;
+
+    let wall_of = |line: usize, side: Option<Side>| {
+        walls
+            .iter()
+            .find(|wall| wall.linedef == line && side.is_none_or(|side| wall.side == side))
+            .map(|wall| Picked::Wall {
+                linedef: wall.linedef,
+                side: wall.side,
+                part: wall.part,
+            })
+    };
+
+    selection.picked = match element {
+        Element::Linedef(line) => wall_of(line, None),
+        Element::Sidedef(sidedef) => {
+            sidedef_line(map, sidedef).and_then(|(line, side)| wall_of(line, Some(side)))
+        }
+        Element::Sector(sector) => Some(Picked::Flat {
+            sector,
+            is_ceiling: false,
+        }),
+        Element::Thing(index) => Some(Picked::Thing { index }),
+        Element::Map | Element::Vertex(_) => None,
+    };
+
+    if let Some((position, angle)) = focus(map, element) {
+        walker.position = position;
+        walker.angle = angle;
+        walker.pitch = 0.;
+
+        for mut transform in cameras.iter_mut() {
+            *transform = walker.eye(map);
+        }
+    }
+}
+
+fn update_panel(results: Res<LintResults>, mut texts: Query<&mut Text, With<LintText>>) {
+    if !results.is_changed() {
+        return;
+    }
+
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = panel_text(&results);
+    }
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<LintResults>();
+}
+
+pub struct LintPlugin;
+
+impl Plugin for LintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            toggle_lint
+                .run_if(resource_exists::<MapManager>())
+                .in_set(OnUpdate(GameState::MapView)),
+        )
+        .add_systems(
+            (jump, update_panel)
+                .chain()
+                .distributive_run_if(resource_exists::<LintResults>())
+                .distributive_run_if(resource_exists::<Walker>())
+                .distributive_run_if(resource_exists::<Selection>())
+                .in_set(OnUpdate(GameState::MapView)),
+        )
+        .add_systems((cleanup, cleanup_panel::<LintPanel>).in_schedule(OnExit(GameState::MapView)));
+    }
+}
@@ -1,12 +1,42 @@
+mod animation;
+mod automap;
+mod export;
 mod flat;
+mod inspector;
+mod lighting;
+mod limits;
+mod lint;
 #[allow(clippy::too_many_arguments)]
 mod mapmanager;
+mod missing;
+mod movers;
+mod panel;
+mod scrolling;
+mod sky;
+mod specials;
 mod state;
+mod stats;
+mod things;
+mod tic;
+mod walk;
 
 use std::env;
 
 use bevy::prelude::*;
+use animation::AnimationPlugin;
+use automap::AutomapPlugin;
+use inspector::InspectorPlugin;
+use lighting::LightingPlugin;
+use lint::LintPlugin;
+use missing::MissingPlugin;
+use movers::MoversPlugin;
+use scrolling::ScrollingPlugin;
+use sky::SkyPlugin;
 use state::StatePlugins;
+use stats::StatsPlugin;
+use things::ThingsPlugin;
+use tic::TicPlugin;
+use walk::WalkPlugin;
 
 use crate::state::GameState;
 use bevy_editor_pls::EditorPlugin;
@@ -20,12 +50,43 @@ struct AppState {
 
 fn main() {
     env::set_var("RUST_BACKTRACE", "1");
+
+    // headless commands, no window is opened
+    let args: Vec<String> = env::args().collect();
+    let result = match args.get(1).map(String::as_str) {
+        Some("export") => Some(export::run(&args[2..])),
+        Some("limits") => Some(limits::run(&args[2..])),
+        Some("lint") => Some(lint::run(&args[2..])),
+        _ => None,
+    };
+
+    if let Some(result) = result {
+        if let Err(err) = result {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     App::new()
         .add_state::<GameState>()
         .add_plugins(DefaultPlugins)
         .add_plugin(EditorPlugin::default())
         .add_startup_system(setup)
         .add_plugins(StatePlugins)
+        .add_plugin(TicPlugin)
+        .add_plugin(LightingPlugin)
+        .add_plugin(AnimationPlugin)
+        .add_plugin(ScrollingPlugin)
+        .add_plugin(SkyPlugin)
+        .add_plugin(MoversPlugin)
+        .add_plugin(WalkPlugin)
+        .add_plugin(ThingsPlugin)
+        .add_plugin(InspectorPlugin)
+        .add_plugin(AutomapPlugin)
+        .add_plugin(StatsPlugin)
+        .add_plugin(LintPlugin)
+        .add_plugin(MissingPlugin)
         .run();
 }
 
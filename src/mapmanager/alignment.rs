@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use super::complete_map::Sector;
+use super::components::WallPart;
+
+/// ML_DONTPEGTOP and ML_DONTPEGBOTTOM linedef flags
+pub const ML_DONTPEGTOP: i16 = 8;
+pub const ML_DONTPEGBOTTOM: i16 = 16;
+
+/// what a wall texture is lined up with before the sidedef y offset
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Anchor {
+    /// first row of the texture at this height
+    Top(i16),
+    /// last row of the texture at this height
+    Bottom(i16),
+}
+
+impl Anchor {
+    /// height of the first texture row, a positive y offset moves the texture up
+    pub fn texture_top(self, tex_height: i16, off_y: i16) -> i32 {
+        let top = match self {
+            Anchor::Top(height) => height as i32,
+            Anchor::Bottom(height) => height as i32 + tex_height as i32,
+        };
+
+        top + off_y as i32
+    }
+}
+
+/// pegging of R_StoreWallRange, `this` is the sector on the side being drawn
+/// and `other` the one behind it on two-sided lines
+pub fn anchor(part: WallPart, flags: i16, this: &Sector, other: Option<&Sector>) -> Anchor {
+    let upper_unpegged = flags & ML_DONTPEGTOP != 0;
+    let lower_unpegged = flags & ML_DONTPEGBOTTOM != 0;
+
+    match (part, other) {
+        (WallPart::Middle, None) if lower_unpegged => Anchor::Bottom(this.floor_height),
+        (WallPart::Middle, Some(other)) if lower_unpegged => {
+            Anchor::Bottom(this.floor_height.max(other.floor_height))
+        }
+        (WallPart::Middle, Some(other)) => Anchor::Top(this.ceil_height.min(other.ceil_height)),
+        // upper textures grow up from the lower ceiling unless unpegged
+        (WallPart::Upper, Some(other)) if !upper_unpegged => Anchor::Bottom(other.ceil_height),
+        // lower textures hang from the higher floor, unpegged they continue
+        // from the ceiling like the wall above them
+        (WallPart::Lower, Some(other)) if !lower_unpegged => Anchor::Top(other.floor_height),
+        // one-sided walls and unpegged uppers and lowers
+        _ => Anchor::Top(this.ceil_height),
+    }
+}
+
+/// texture coordinates of a wall quad from `bottom` to `top` in the order
+/// start bottom, start top, end bottom, end top, where the start is the first
+/// vertex of the side as seen from the front of it
+pub fn wall_uvs(length: f32, bottom: i16, top: i16, texture_top: i32, off_x: i16, size: Vec2) -> [Vec2; 4] {
+    let start = off_x as f32 / size.x;
+    let end = (off_x as f32 + length) / size.x;
+    let row = |height: i16| (texture_top - height as i32) as f32 / size.y;
+
+    [
+        Vec2::new(start, row(bottom)),
+        Vec2::new(start, row(top)),
+        Vec2::new(end, row(bottom)),
+        Vec2::new(end, row(top)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sector(floor_height: i16, ceil_height: i16) -> Sector {
+        Sector {
+            floor_height,
+            ceil_height,
+            ..default()
+        }
+    }
+
+    /// texture rows at the bottom and top of a wall part
+    fn rows(part: WallPart, flags: i16, this: &Sector, other: Option<&Sector>, off_y: i16, tex_height: i16) -> (f32, f32) {
+        let (bottom, top) = match (part, other) {
+            (WallPart::Upper, Some(other)) => (other.ceil_height, this.ceil_height),
+            (WallPart::Lower, Some(other)) => (this.floor_height, other.floor_height),
+            (_, Some(other)) => (
+                this.floor_height.max(other.floor_height),
+                this.ceil_height.min(other.ceil_height),
+            ),
+            (_, None) => (this.floor_height, this.ceil_height),
+        };
+
+        let texture_top = anchor(part, flags, this, other).texture_top(tex_height, off_y);
+        let uvs = wall_uvs(64., bottom, top, texture_top, 0, Vec2::new(64., tex_height as f32));
+
+        (uvs[0].y * tex_height as f32, uvs[1].y * tex_height as f32)
+    }
+
+    #[test]
+    fn one_sided_hangs_from_the_ceiling() {
+        let room = sector(0, 128);
+        assert_eq!(rows(WallPart::Middle, 0, &room, None, 0, 128), (128., 0.));
+        assert_eq!(rows(WallPart::Middle, 0, &sector(0, 96), None, 0, 128), (96., 0.));
+    }
+
+    #[test]
+    fn one_sided_lower_unpegged_stands_on_the_floor() {
+        let room = sector(0, 96);
+        assert_eq!(rows(WallPart::Middle, ML_DONTPEGBOTTOM, &room, None, 0, 128), (128., 32.));
+    }
+
+    #[test]
+    fn y_offset_moves_the_texture_up() {
+        let room = sector(0, 128);
+        assert_eq!(rows(WallPart::Middle, 0, &room, None, 16, 128), (144., 16.));
+        assert_eq!(rows(WallPart::Middle, 0, &room, None, -16, 128), (112., -16.));
+    }
+
+    #[test]
+    fn upper_grows_from_the_lower_ceiling() {
+        let (room, window) = (sector(0, 128), sector(32, 96));
+        assert_eq!(rows(WallPart::Upper, 0, &room, Some(&window), 0, 64), (64., 32.));
+    }
+
+    #[test]
+    fn upper_unpegged_hangs_from_the_ceiling() {
+        let (room, window) = (sector(0, 128), sector(32, 96));
+        assert_eq!(rows(WallPart::Upper, ML_DONTPEGTOP, &room, Some(&window), 0, 64), (32., 0.));
+    }
+
+    #[test]
+    fn lower_hangs_from_the_higher_floor() {
+        let (room, step) = (sector(0, 128), sector(24, 128));
+        assert_eq!(rows(WallPart::Lower, 0, &room, Some(&step), 0, 64), (24., 0.));
+    }
+
+    #[test]
+    fn lower_unpegged_continues_from_the_ceiling() {
+        let (room, step) = (sector(0, 128), sector(24, 128));
+        assert_eq!(rows(WallPart::Lower, ML_DONTPEGBOTTOM, &room, Some(&step), 0, 64), (128., 104.));
+    }
+
+    #[test]
+    fn lower_uses_the_offset_of_its_own_side() {
+        let (room, step) = (sector(0, 128), sector(24, 128));
+        assert_eq!(rows(WallPart::Lower, 0, &room, Some(&step), 8, 64), (32., 8.));
+    }
+
+    #[test]
+    fn masked_middle_uses_the_opening() {
+        let (room, window) = (sector(0, 128), sector(32, 96));
+        assert_eq!(rows(WallPart::Middle, 0, &room, Some(&window), 0, 128), (64., 0.));
+        assert_eq!(
+            anchor(WallPart::Middle, ML_DONTPEGBOTTOM, &room, Some(&window)),
+            Anchor::Bottom(32)
+        );
+        assert_eq!(
+            anchor(WallPart::Middle, ML_DONTPEGBOTTOM, &window, Some(&room)),
+            Anchor::Bottom(32)
+        );
+    }
+
+    #[test]
+    fn door_face_moves_with_the_door() {
+        let room = sector(0, 128);
+
+        for open in [0, 32, 64] {
+            let door = sector(0, open);
+
+            // the bottom row of the texture rides on the door edge
+            assert_eq!(rows(WallPart::Upper, 0, &room, Some(&door), 0, 128).0, 128.);
+            // unpegged, the texture stays put while the door edge moves over it
+            assert_eq!(
+                rows(WallPart::Upper, ML_DONTPEGTOP, &room, Some(&door), 0, 128),
+                ((128 - open) as f32, 0.)
+            );
+        }
+    }
+
+    #[test]
+    fn columns_start_at_the_x_offset() {
+        let uvs = wall_uvs(96., 0, 64, 64, 32, Vec2::new(64., 64.));
+        assert_eq!(uvs[0].x, 0.5);
+        assert_eq!(uvs[2].x, 2.);
+        assert_eq!(uvs[1].x, uvs[0].x);
+    }
+}
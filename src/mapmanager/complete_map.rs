@@ -1,5 +1,11 @@
+use bevy::math::Vec2;
 use bevy::utils::HashMap;
 
+/// texture or flat name without its NUL padding
+pub fn trimmed(name: &[u8; 8]) -> String {
+    String::from_utf8_lossy(name).trim_end_matches('\0').to_string()
+}
+
 #[derive(Clone, Default)]
 pub struct TexturePatch {
     pub origin_x: i16,
@@ -76,5 +82,240 @@ pub struct CompleteMap {
     pub sector_vec: Vec<Sector>,
     pub sidefef_vec: Vec<Sidedef>,
     pub pnames: Vec<Vec<String>>,
+    pub texture_names: Vec<Vec<String>>,
     pub texture_defs: HashMap<String, TextureEntry>,
 }
+
+impl CompleteMap {
+    /// sectors sharing a two-sided linedef with `sector`
+    pub fn sector_neighbours(&self, sector: usize) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = Vec::new();
+
+        for i in 0..self.linedef_vec.len() {
+            let (Some(front), Some(back)) = self.line_sectors(i) else {
+                continue;
+            };
+
+            let other = if front == sector {
+                back
+            } else if back == sector {
+                front
+            } else {
+                continue;
+            };
+
+            if other != sector && !neighbours.contains(&other) {
+                neighbours.push(other);
+            }
+        }
+
+        neighbours
+    }
+
+    /// front and back sector of a linedef, none for a missing side or one
+    /// whose sidedef or sector is out of range
+    pub fn line_sectors(&self, line: usize) -> (Option<usize>, Option<usize>) {
+        let linedef = &self.linedef_vec[line];
+        let sector = |side: i16| {
+            let sidedef = self.sidefef_vec.get(usize::try_from(side).ok()?)?;
+            let sector = usize::try_from(sidedef.sector).ok()?;
+            (sector < self.sector_vec.len()).then_some(sector)
+        };
+
+        (sector(linedef.front_sidedef), sector(linedef.back_sidedef))
+    }
+
+    /// start and end of a linedef, none when a vertex is out of range
+    pub fn line_ends(&self, line: usize) -> Option<(Vec2, Vec2)> {
+        let linedef = &self.linedef_vec[line];
+        let vert = |index: i16| {
+            let vert = self.vert_vec.get(usize::try_from(index).ok()?)?;
+            Some(Vec2::new(vert.x as f32, vert.y as f32))
+        };
+
+        Some((vert(linedef.start_vert)?, vert(linedef.end_vert)?))
+    }
+
+    pub fn tagged_sectors(&self, tag: i16) -> Vec<usize> {
+        self.sector_vec
+            .iter()
+            .enumerate()
+            .filter(|(_, sector)| sector.tag == tag)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// P_PointOnLineSide, the front side is on the right of the line, a line
+    /// missing a vertex has everything in front
+    pub fn point_on_front(&self, line: usize, point: Vec2) -> bool {
+        let Some((start, end)) = self.line_ends(line) else {
+            return true;
+        };
+
+        let delta = end - start;
+
+        (point.y - start.y) * delta.x < (point.x - start.x) * delta.y
+    }
+
+    /// fraction along `from` -> `to` where the segment goes through the linedef
+    pub fn segment_intercept(&self, line: usize, from: Vec2, to: Vec2) -> Option<f32> {
+        let (start, end) = self.line_ends(line)?;
+
+        let cross = |o: Vec2, a: Vec2, b: Vec2| (a - o).perp_dot(b - o);
+
+        let d1 = cross(start, end, from);
+        let d2 = cross(start, end, to);
+        let d3 = cross(from, to, start);
+        let d4 = cross(from, to, end);
+
+        (d1 * d2 < 0. && d3 * d4 <= 0.).then(|| d1 / (d1 - d2))
+    }
+
+    /// sector containing a map point, found by casting a ray along +x
+    pub fn sector_at(&self, point: Vec2) -> Option<usize> {
+        let mut closest: Option<(f32, usize)> = None;
+
+        for i in 0..self.linedef_vec.len() {
+            let Some((start, end)) = self.line_ends(i) else {
+                continue;
+            };
+            let (x1, y1, x2, y2) = (start.x, start.y, end.x, end.y);
+
+            if (y1 <= point.y) == (y2 <= point.y) {
+                continue;
+            }
+
+            let x = x1 + (point.y - y1) / (y2 - y1) * (x2 - x1);
+
+            if x < point.x || closest.map_or(false, |(distance, _)| x - point.x >= distance) {
+                continue;
+            }
+
+            closest = Some((x - point.x, i));
+        }
+
+        let (_, line) = closest?;
+        let (front, back) = self.line_sectors(line);
+
+        if self.point_on_front(line, point) {
+            front
+        } else {
+            back
+        }
+    }
+
+    //this took 3 days to figure out bruh
+    pub fn detect_shapes(&self, sector: &Sector) -> Vec<Vec<i16>> {
+        let mut shapes: Vec<Vec<i16>> = Vec::new();
+        let mut order_count: usize = 0;
+
+        let mut analyzed: Vec<i16> = Vec::new();
+
+        let mut current_linedef = -1;
+        let mut current_vert = -1;
+
+        while order_count < sector.linedefs.len() {
+            let mut shape: Vec<i16> = Vec::new();
+
+            let mut found = false;
+
+            for i in sector.linedefs.clone() {
+                if !analyzed.contains(&i) {
+                    current_linedef = i;
+                    found = true;
+                    break;
+                }
+            }
+
+            if !found {
+                break;
+            }
+
+            current_vert = self.linedef_vec[current_linedef as usize].end_vert;
+
+            // println!("\nShape");
+
+            loop {
+                shape.push(current_linedef);
+
+                analyzed.push(current_linedef);
+
+                let prev = order_count;
+
+                for line in sector.linedefs.clone() {
+                    if shape.contains(&line) {
+                        continue;
+                    }
+
+                    let linedef2 = &self.linedef_vec[line as usize];
+
+                    if current_vert == linedef2.start_vert || current_vert == linedef2.end_vert {
+                        // println!("Found line {} for line {}", line + 1, current_linedef + 1);
+                        current_linedef = line;
+                        current_vert = if linedef2.start_vert == current_vert {
+                            linedef2.end_vert
+                        } else {
+                            linedef2.start_vert
+                        };
+                        order_count += 1;
+                        break;
+                    }
+                }
+
+                if order_count == prev {
+                    // println!("Did not find anything for line {}", current_linedef + 1);
+                    break;
+                }
+            }
+
+            // println!("Final shape");
+
+            // for i in shape.clone() {
+            //     println!("{}", i + 1);
+            // }
+
+            shapes.push(shape);
+        }
+
+        shapes
+    }
+
+    pub fn get_linedef_vector_as_vertices(&self, vec: &Vec<i16>) -> Vec<f64> {
+        let mut vertices: Vec<f64>;
+        vertices = Vec::new();
+        let mut vert_contains_list: Vec<Vec2>;
+        vert_contains_list = Vec::new();
+
+        for line_index in vec {
+            let linedef = &self.linedef_vec[*line_index as usize];
+
+            let vert1 = &self.vert_vec[linedef.start_vert as usize];
+            let vert2 = &self.vert_vec[linedef.end_vert as usize];
+
+            let coords = vec![
+                vert1.x as f64,
+                vert1.y as f64,
+                vert2.x as f64,
+                vert2.y as f64,
+            ];
+
+            if !vert_contains_list.contains(&Vec2::new(coords[0] as f32, coords[1] as f32)) {
+                vert_contains_list.push(Vec2::new(coords[0] as f32, coords[1] as f32));
+            }
+
+            if !vert_contains_list.contains(&Vec2::new(coords[2] as f32, coords[3] as f32)) {
+                vert_contains_list.push(Vec2::new(coords[2] as f32, coords[3] as f32));
+            }
+        }
+
+        // print!("\n");
+
+        for vec in vert_contains_list {
+            // println!("({}, {})", vec.x, vec.y);
+            vertices.push(vec.x as f64);
+            vertices.push(vec.y as f64);
+        }
+
+        return vertices;
+    }
+}
@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Side {
+    Front,
+    Back,
+}
+
+/// which texture of a sidedef a wall quad shows
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WallPart {
+    Upper,
+    Middle,
+    Lower,
+}
+
+/// wall quad generated from one side of a linedef
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub struct WallSurface {
+    pub linedef: usize,
+    pub side: Side,
+    pub part: WallPart,
+}
+
+/// floor or ceiling polygon of a sector
+#[derive(Component, Clone, Copy)]
+pub struct FlatSurface {
+    pub sector: usize,
+    pub is_ceiling: bool,
+}
+
+/// sprite billboard of an entry in `things_vec`
+#[derive(Component, Clone, Copy)]
+pub struct ThingEntity {
+    pub index: usize,
+}
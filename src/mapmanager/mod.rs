@@ -1,26 +1,94 @@
+pub(crate) mod alignment;
 pub(crate) mod complete_map;
+pub(crate) mod components;
+mod surfaces;
 
 use crate::flat::Flat;
+use crate::lighting::LitSurface;
+use crate::sky::SkyMaterial;
 use bevy::prelude::*;
 use bevy::render::mesh;
 use bevy::render::mesh::PrimitiveTopology;
 use bevy::render::render_resource::{AddressMode, Extent3d, Face, TextureFormat};
 use bevy::render::texture::ImageSampler;
 use bevy::utils::hashbrown::HashMap;
+use std::collections::BTreeSet;
 use bevy_earcutr::*;
 use complete_map::*;
-use tinywad::lump::{LumpData, LumpKind};
+use alignment::{wall_uvs, Anchor};
+use components::{WallPart, WallSurface};
+use tinywad::lump::{LumpData, LumpKind, Namespace};
 use tinywad::lumps::palette::Palettes;
 use tinywad::lumps::patch::DoomImage;
+use tinywad::lumps::png::{is_png, png_size, PngImage};
 use tinywad::models::lump::Lump;
 use tinywad::wad::Wad;
 
+/// side in pixels of the squares of the missing texture placeholder
+const PLACEHOLDER_SQUARE: usize = 8;
+
+/// lumps that make up a map after its marker
+const MAP_LUMPS: [&str; 10] = [
+    "THINGS", "LINEDEFS", "SIDEDEFS", "VERTEXES", "SEGS", "SSECTORS", "NODES", "SECTORS", "REJECT", "BLOCKMAP",
+];
+
+/// name of the texture a part of a sidedef shows
+fn wall_texture(sidedef: &Sidedef, part: WallPart) -> String {
+    let texture = match part {
+        WallPart::Upper => &sidedef.upper_tex,
+        WallPart::Middle => &sidedef.mid_tex,
+        WallPart::Lower => &sidedef.lower_tex,
+    };
+
+    String::from_utf8_lossy(texture).to_string()
+}
+
+/// maps of a WAD, found through their THINGS lumps
+pub fn wad_maps(wad: &Wad) -> Vec<(i32, String)> {
+    let lumps = wad.lumps();
+    let mut maps: Vec<(i32, String)> = Vec::new();
+
+    for (index, lump) in lumps.iter().enumerate() {
+        let id = lump.data().metadata.id_ascii();
+
+        let Some(suffix) = id.strip_prefix("THINGS") else {
+            continue;
+        };
+
+        if index == 0 {
+            continue;
+        }
+
+        let map_ind = if suffix.is_empty() {
+            0
+        } else {
+            match suffix.parse() {
+                Ok(map_ind) => map_ind,
+                Err(_) => continue,
+            }
+        };
+
+        maps.push((map_ind, lumps[index - 1].data().metadata.name_ascii()));
+    }
+
+    maps
+}
+
+#[derive(Resource)]
 pub struct MapManager {
     palette: Palettes,
     pub res_wads: Vec<Wad>,
     pub map: CompleteMap,
+    pub map_name: String,
+    pub sky_material: Handle<SkyMaterial>,
     pub tex_map: HashMap<String, Handle<Image>>,
     pub mat_map: HashMap<String, Handle<StandardMaterial>>,
+    /// textures and flats that could not be found, drawn with the placeholder
+    pub missing: BTreeSet<String>,
+    /// checkerboard materials for missing names, back and front face culled
+    placeholders: [Option<Handle<StandardMaterial>>; 2],
+    /// world size of the loaded images by name, see `world_size`
+    sizes: HashMap<String, Vec2>,
 }
 
 impl MapManager {
@@ -28,9 +96,14 @@ impl MapManager {
         let mut manager = MapManager {
             res_wads: Vec::new(),
             map: CompleteMap::default(),
+            map_name: String::new(),
+            sky_material: Handle::default(),
             palette: Palettes::default(),
             mat_map: HashMap::new(),
             tex_map: HashMap::new(),
+            missing: BTreeSet::new(),
+            placeholders: [None, None],
+            sizes: HashMap::new(),
         };
 
         let mut pwad = Wad::new();
@@ -53,6 +126,18 @@ impl MapManager {
 
         info!("KUR {}", map_ind_string);
 
+        // the map marker is the lump right before THINGS
+        let things_id = format!("THINGS{}", map_ind_string);
+        let lumps = manager.res_wads[0].lumps();
+        if let Some(index) = lumps
+            .iter()
+            .position(|lump| lump.data().metadata.id_ascii() == things_id)
+        {
+            if index > 0 {
+                manager.map_name = lumps[index - 1].data().metadata.name_ascii();
+            }
+        }
+
         let things = manager.res_wads[0].lump(format!("THINGS{}", map_ind_string).as_str()).unwrap().data();
         let things_num = things.metadata.size as usize / 10;
 
@@ -84,6 +169,8 @@ impl MapManager {
                 }
             }
 
+            manager.map.texture_names.push(Vec::new());
+
             let mut names: Vec<String> = Vec::new();
 
             let pnames_opt = wad.lump("PNAMES");
@@ -141,6 +228,9 @@ impl MapManager {
                 let mut tex_name = [0; 8];
                 tex_name.copy_from_slice(&texture1.buffer[(offset)..(offset + 8)]);
 
+                manager.map.texture_names[wad_ind]
+                    .push(std::str::from_utf8(tex_name.as_slice()).unwrap().to_string());
+
                 if manager
                     .map
                     .texture_defs
@@ -347,63 +437,182 @@ impl MapManager {
         return manager;
     }
 
+    /// maps of the PWAD as the index `new` takes and the marker lump name
+    pub fn map_list(&self) -> Vec<(i32, String)> {
+        wad_maps(&self.res_wads[0])
+    }
+
+    /// a lump of the loaded map like SEGS or BLOCKMAP, among those following its marker
+    pub fn map_lump(&self, name: &str) -> Option<LumpData> {
+        let lumps = self.res_wads[0].lumps();
+        let marker = lumps
+            .iter()
+            .position(|lump| lump.data().metadata.name_ascii() == self.map_name)?;
+
+        lumps
+            .iter()
+            .skip(marker + 1)
+            .map(|lump| lump.data())
+            .take_while(|data| MAP_LUMPS.contains(&data.metadata.name_ascii().as_str()))
+            .find(|data| data.metadata.name_ascii() == name)
+    }
+
+    /// whether any loaded WAD has a flat by that name
+    pub fn is_flat(&self, name: &str) -> bool {
+        self.res_wads
+            .iter()
+            .any(|wad| wad.lump_in(Namespace::Flats, name).is_some())
+    }
+
+    /// whether `name` gives a wall image, looked up in the order `get_image`
+    /// tries: TEXTUREx, a TX_ texture or a flat, any lump, then a HI_ texture
+    pub fn is_texture(&self, name: &str) -> bool {
+        self.map
+            .texture_defs
+            .keys()
+            .any(|key| key.trim_end_matches('\0').eq_ignore_ascii_case(name))
+            || [Namespace::Textures, Namespace::Flats]
+                .into_iter()
+                .any(|namespace| self.find_lump(name, namespace).is_some())
+            || self.res_wads.iter().any(|wad| wad.lump(name).is_some())
+            || self.find_lump(name, Namespace::HiRes).is_some()
+    }
+
+    /// width of a patch lump, looked up where texture composition finds it
+    pub fn patch_width(&self, name: &str) -> Option<i32> {
+        let data = [Namespace::Patches, Namespace::Global, Namespace::Sprites]
+            .into_iter()
+            .find_map(|namespace| self.find_lump(name, namespace))?;
+
+        if is_png(&data.buffer) {
+            return png_size(&data.buffer).map(|(width, _)| width as i32);
+        }
+
+        Some(i16::from_le_bytes([*data.buffer.first()?, *data.buffer.get(1)?]) as i32)
+    }
+
+    /// drop the cached textures and materials from their asset stores
+    pub fn free_assets(
+        &mut self,
+        images: &mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+        sky_materials: &mut Assets<SkyMaterial>,
+    ) {
+        for (_, material) in self.mat_map.drain() {
+            materials.remove(material);
+        }
+
+        for (_, image) in self.tex_map.drain() {
+            images.remove(image);
+        }
+
+        self.sizes.clear();
+
+        for placeholder in self.placeholders.iter_mut().filter_map(Option::take) {
+            if let Some(material) = materials.remove(placeholder) {
+                if let Some(image) = material.base_color_texture {
+                    images.remove(image);
+                }
+            }
+        }
+
+        sky_materials.remove(std::mem::take(&mut self.sky_material));
+    }
+
+    /// wall quad of one part of a sidedef between two heights, `start` and
+    /// `end` are the line vertices as seen from the front of the side
     pub fn generate_wall(
         &mut self,
         commands: &mut Commands,
         meshes: &mut Assets<Mesh>,
         mut images: &mut Assets<Image>,
         mut materials: &mut Assets<StandardMaterial>,
-        vert1: Vert,
-        vert2: Vert,
-        mut floor_height: i16,
-        mut ceiling_height: i16,
-        off_x: i16,
-        off_y: i16,
-        tex_name: String,
-        backface: bool,
-        pegged: i16,
-        mid: bool,
+        start: &Vert,
+        end: &Vert,
+        floor_height: i16,
+        ceiling_height: i16,
+        sidedef: &Sidedef,
+        anchor: Anchor,
+        masked: bool,
+        surface: WallSurface,
     ) {
+        let tex_name = wall_texture(sidedef, surface.part);
+
         if tex_name.as_str().trim_matches(char::from(0)) == "-" {
             return;
         }
 
-        let mut tex_width = 1.;
-        let mut tex_height = 1.;
-
-        if self.map.texture_defs.contains_key(&tex_name) {
-            match self
-                .generate_image_from_texentry(images, self.map.texture_defs[&tex_name].clone())
-            {
-                Ok(tex) => {
-                    let img = images.get(&tex);
-                    tex_width = img.unwrap().size().x;
-                    tex_height = img.unwrap().size().y;
-                }
-                Err(err) => {
-                    error!(err);
-                }
-            }
+        let material = self.get_texture(&mut images, &mut materials, tex_name, false, masked);
+
+        let Some(mesh) = self.wall_mesh(start, end, floor_height, ceiling_height, sidedef, anchor, masked, surface.part) else {
+            return;
+        };
+
+        commands
+            .spawn(PbrBundle {
+                mesh: meshes.add(mesh),
+                material,
+                ..default()
+            })
+            .insert(LitSurface {
+                sector: sidedef.sector as usize,
+            })
+            .insert(surface);
+    }
+
+    /// geometry of a wall quad, none when the part has no texture or a
+    /// masked middle texture falls outside the opening
+    pub fn wall_mesh(
+        &self,
+        start: &Vert,
+        end: &Vert,
+        mut floor_height: i16,
+        mut ceiling_height: i16,
+        sidedef: &Sidedef,
+        anchor: Anchor,
+        masked: bool,
+        part: WallPart,
+    ) -> Option<Mesh> {
+        let tex_name = wall_texture(sidedef, part);
+
+        if tex_name.as_str().trim_matches(char::from(0)) == "-" {
+            return None;
         }
 
-        if mid {
-            if pegged == 0 {
-                floor_height = ceiling_height - tex_height as i16;
-            } else {
-                ceiling_height = floor_height + tex_height as i16;
+        let mut tex_width = 1;
+        let mut tex_height = 1;
+
+        // walls are rebuilt while sectors move, so size them from the
+        // texture definition instead of composing the image every time
+        if let Some(entry) = self.map.texture_defs.get(&tex_name) {
+            tex_width = entry.width;
+            tex_height = entry.height;
+        } else if let Some(size) = self.world_size(&tex_name) {
+            tex_width = size.x as i16;
+            tex_height = size.y as i16;
+        }
+
+        let texture_top = anchor.texture_top(tex_height, sidedef.y_off);
+
+        // masked middle textures are drawn once instead of tiling, cut to the opening
+        if masked {
+            floor_height = floor_height.max((texture_top - tex_height as i32) as i16);
+            ceiling_height = ceiling_height.min(texture_top as i16);
+
+            if ceiling_height <= floor_height {
+                return None;
             }
-            floor_height += off_y;
-            ceiling_height += off_y;
         }
 
-        let len = (Vec2::new(vert1.x as f32, vert1.y as f32)
-            - Vec2::new(vert2.x as f32, vert2.y as f32))
-        .length();
-        let height = (ceiling_height - floor_height) as f32;
-        let u = len / (tex_width);
-        let v = height / (tex_height);
-        let ox = off_x as f32 / tex_width;
-        let oy = off_y as f32 / tex_height;
+        let len = (Vec2::new(start.x as f32, start.y as f32) - Vec2::new(end.x as f32, end.y as f32)).length();
+        let uvs = wall_uvs(
+            len,
+            floor_height,
+            ceiling_height,
+            texture_top,
+            sidedef.x_off,
+            Vec2::new(tex_width as f32, tex_height as f32),
+        );
 
         let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
 
@@ -416,69 +625,37 @@ impl MapManager {
         let mut indices: Vec<u32>;
         indices = Vec::new();
 
-        let mut uvs: Vec<Vec2>;
-        uvs = Vec::new();
-
         vertices.push(Vec3::new(
-            -vert1.x as f32,
+            -start.x as f32,
             floor_height as f32,
-            vert1.y as f32,
+            start.y as f32,
         ));
         vertices.push(Vec3::new(
-            -vert1.x as f32,
+            -start.x as f32,
             ceiling_height as f32,
-            vert1.y as f32,
+            start.y as f32,
         ));
         vertices.push(Vec3::new(
-            -vert2.x as f32,
+            -end.x as f32,
             floor_height as f32,
-            vert2.y as f32,
+            end.y as f32,
         ));
         vertices.push(Vec3::new(
-            -vert2.x as f32,
+            -end.x as f32,
             ceiling_height as f32,
-            vert2.y as f32,
+            end.y as f32,
         ));
 
-        if pegged == 2 {
-            let sheight = ceiling_height - floor_height;
-            let sv = sheight as f32 / (tex_height);
-
-            uvs.push(Vec2::new(ox, 1. - sv + v));
-            uvs.push(Vec2::new(ox, 1. - sv));
-            uvs.push(Vec2::new(u + ox, 1. - sv + v));
-            uvs.push(Vec2::new(u + ox, 1. - sv));
-        } else if pegged == 1 {
-            uvs.push(Vec2::new(ox, v + oy));
-            uvs.push(Vec2::new(ox, oy));
-            uvs.push(Vec2::new(u + ox, v + oy));
-            uvs.push(Vec2::new(u + ox, oy));
-        } else {
-            uvs.push(Vec2::new(ox, 1. - oy));
-            uvs.push(Vec2::new(ox, 1. - v - oy));
-            uvs.push(Vec2::new(u + ox, 1. - oy));
-            uvs.push(Vec2::new(u + ox, 1. - v - oy));
-        }
-
-        if backface {
-            indices.push(0);
-            indices.push(1);
-            indices.push(2);
-            indices.push(2);
-            indices.push(1);
-            indices.push(3);
-        } else {
-            indices.push(2);
-            indices.push(1);
-            indices.push(0);
-            indices.push(3);
-            indices.push(1);
-            indices.push(2);
-        }
+        indices.push(2);
+        indices.push(1);
+        indices.push(0);
+        indices.push(3);
+        indices.push(1);
+        indices.push(2);
 
         let normal = Vec3::new(
-            vert1.x as f32 - vert2.x as f32,
-            vert1.y as f32 - vert2.y as f32,
+            start.x as f32 - end.x as f32,
+            start.y as f32 - end.y as f32,
             0.,
         )
         .cross(Vec3::Y)
@@ -489,93 +666,13 @@ impl MapManager {
         normals.push(normal);
 
         mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs.to_vec());
 
         mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
 
         mesh.set_indices(Some(mesh::Indices::U32(indices)));
 
-        commands.spawn(PbrBundle {
-            mesh: meshes.add(mesh),
-            material: self.get_texture(&mut images, &mut materials, tex_name, false),
-            ..default()
-        });
-    }
-
-    //this took 3 days to figure out bruh
-    pub fn detect_shapes(&self, sector: &Sector) -> Vec<Vec<i16>> {
-        let mut shapes: Vec<Vec<i16>> = Vec::new();
-        let mut order_count: usize = 0;
-
-        let mut analyzed: Vec<i16> = Vec::new();
-
-        let mut current_linedef = -1;
-        let mut current_vert = -1;
-
-        while order_count < sector.linedefs.len() {
-            let mut shape: Vec<i16> = Vec::new();
-
-            let mut found = false;
-
-            for i in sector.linedefs.clone() {
-                if !analyzed.contains(&i) {
-                    current_linedef = i;
-                    found = true;
-                    break;
-                }
-            }
-
-            if !found {
-                break;
-            }
-
-            current_vert = self.map.linedef_vec[current_linedef as usize].end_vert;
-
-            // println!("\nShape");
-
-            loop {
-                shape.push(current_linedef);
-
-                analyzed.push(current_linedef);
-
-                let prev = order_count;
-
-                for line in sector.linedefs.clone() {
-                    if shape.contains(&line) {
-                        continue;
-                    }
-
-                    let linedef2 = &self.map.linedef_vec[line as usize];
-
-                    if current_vert == linedef2.start_vert || current_vert == linedef2.end_vert {
-                        // println!("Found line {} for line {}", line + 1, current_linedef + 1);
-                        current_linedef = line;
-                        current_vert = if linedef2.start_vert == current_vert {
-                            linedef2.end_vert
-                        } else {
-                            linedef2.start_vert
-                        };
-                        order_count += 1;
-                        break;
-                    }
-                }
-
-                if order_count == prev {
-                    // println!("Did not find anything for line {}", current_linedef + 1);
-                    break;
-                }
-            }
-
-            // println!("Final shape");
-
-            // for i in shape.clone() {
-            //     println!("{}", i + 1);
-            // }
-
-            shapes.push(shape);
-        }
-
-        shapes
+        Some(mesh)
     }
 
     pub fn triangulate_polygon_with_holes(body: &mut Vec<f64>, holes: &[Vec<f64>]) -> Option<Mesh> {
@@ -598,9 +695,40 @@ impl MapManager {
         return builder.build();
     }
 
+    /// first lump named `name` in the loaded WADs within `namespace`
+    fn find_lump(&self, name: &str, namespace: Namespace) -> Option<LumpData> {
+        self.res_wads
+            .iter()
+            .find_map(|wad| wad.lump_in(namespace, name))
+            .map(|lump| lump.data())
+    }
+
+    /// image of the first lump named `name` in one of the `namespaces`, tried
+    /// in order so a flat and a patch sharing a name are told apart
+    fn get_namespaced(
+        &mut self,
+        images: &mut Assets<Image>,
+        name: &str,
+        namespaces: &[Namespace],
+    ) -> Option<Result<Handle<Image>, String>> {
+        for namespace in namespaces {
+            let key = format!("{}_{:?}", name, namespace);
+
+            if let Some(image) = self.tex_map.get(&key) {
+                return Some(Ok(image.clone()));
+            }
+
+            if let Some(data) = self.find_lump(name, *namespace) {
+                return Some(self.decode_lump(images, key, data));
+            }
+        }
+
+        None
+    }
+
     fn get_patch(
         &mut self,
-        mut images: &mut Assets<Image>,
+        images: &mut Assets<Image>,
         mut name: String,
     ) -> Result<Handle<Image>, String> {
         name = name.as_str().replace("_flip", "");
@@ -631,16 +759,41 @@ impl MapManager {
             ));
         };
 
+        self.decode_lump(images, name, texture_lump_data)
+    }
+
+    /// image of a patch, flat or PNG lump, cached under `key`
+    fn decode_lump(
+        &mut self,
+        images: &mut Assets<Image>,
+        key: String,
+        texture_lump_data: LumpData,
+    ) -> Result<Handle<Image>, String> {
+        let name = texture_lump_data.metadata.name_ascii();
+
         if texture_lump_data.metadata.size <= 0 {
             return Err("Lump size was 0".parse().unwrap());
         }
 
-        let (image_data, width, height) = if texture_lump_data.kind == LumpKind::Flat {
+        let (image_data, width, height) = if is_png(&texture_lump_data.buffer) {
+            let mut png = PngImage::new(texture_lump_data);
+
+            png.parse();
+
+            let pixels = png.buffer();
+
+            if pixels.is_empty() {
+                return Err(format!("Could not decode PNG {}", name));
+            }
+
+            (pixels, png.width, png.height)
+        } else if texture_lump_data.kind == LumpKind::Flat {
+            let flat = Flat::from_lump(texture_lump_data.buffer.as_slice());
+
             (
-                Flat::from_lump(texture_lump_data.buffer.as_slice())
-                    .get_image(self.palette.palette().unwrap()),
-                64,
-                64,
+                flat.get_image(self.palette.palette().unwrap()),
+                flat.width as u32,
+                flat.height as u32,
             )
         } else {
             let mut doom_image = DoomImage::new(self.palette.clone(), texture_lump_data);
@@ -649,21 +802,18 @@ impl MapManager {
 
             (
                 doom_image.buffer(),
-                doom_image.img_info.width,
-                doom_image.img_info.height,
+                doom_image.img_info.width as u32,
+                doom_image.img_info.height as u32,
             )
         };
 
         if width == 0 || height == 0 {
-            return Err(format!(
-                "Width or height was 0 {}",
-                name.as_str().trim_matches(char::from(0))
-            ));
+            return Err(format!("Width or height was 0 {}", name));
         };
 
         let ext: Extent3d = Extent3d {
-            width: width as u32,
-            height: height as u32,
+            width,
+            height,
             ..default()
         };
 
@@ -683,7 +833,7 @@ impl MapManager {
 
         let handle = images.add(coolasstexture);
 
-        self.tex_map.insert(name.clone(), handle.clone());
+        self.tex_map.insert(key, handle.clone());
 
         Ok(handle)
     }
@@ -696,10 +846,16 @@ impl MapManager {
         let mut data: Vec<u8> = vec![0; entry.width as usize * entry.height as usize * 4];
 
         for patch in entry.patches {
-            match self.get_patch(
-                images,
-                self.map.pnames[entry.wad_ind][patch.patch as usize].clone(),
-            ) {
+            let name = self.map.pnames[entry.wad_ind][patch.patch as usize]
+                .trim_end_matches('\0')
+                .to_uppercase();
+
+            // patches can sit outside the markers but never among the flats
+            let image = self
+                .get_namespaced(images, &name, &[Namespace::Patches, Namespace::Global, Namespace::Sprites])
+                .unwrap_or_else(|| Err(format!("Could not get lump for {}", name)));
+
+            match image {
                 Ok(image) => {
                     if patch.origin_x < 0 || patch.origin_y < 0 {
                         continue;
@@ -717,6 +873,11 @@ impl MapManager {
                             let dest_index =
                                 (offset_y + y) * entry.width as usize * 4 + (offset_x + x) * 4;
 
+                            // gaps between the posts of a patch keep what is under them
+                            if pixels[src_index + 3] == 0 {
+                                continue;
+                            }
+
                             for i in 0..4 {
                                 if dest_index + i >= data.len() {
                                     break;
@@ -751,44 +912,97 @@ impl MapManager {
         Ok(images.add(image))
     }
 
-    pub fn get_texture(
+    /// image for a wall texture or, failing that, a TX_ texture, a flat or
+    /// any patch lump, replaced by its HI_ version when there is one
+    pub fn get_image(
         &mut self,
-        mut images: &mut Assets<Image>,
-        mut materials: &mut Assets<StandardMaterial>,
-        mut name: String,
-        flip: bool,
-    ) -> Handle<StandardMaterial> {
-        if flip {
-            name += "_flip";
-        }
+        images: &mut Assets<Image>,
+        name: String,
+    ) -> Result<Handle<Image>, String> {
+        let resource = name.replace("_flip", "").trim_end_matches('\0').to_uppercase();
 
-        if self.mat_map.contains_key(&name) {
-            return self.mat_map[&name].clone();
+        let image = if self.map.texture_defs.contains_key(&name) {
+            self.generate_image_from_texentry(images, self.map.texture_defs[&name].clone())
+        } else if let Some(image) =
+            self.get_namespaced(images, &resource, &[Namespace::Textures, Namespace::Flats])
+        {
+            image
+        } else {
+            self.get_patch(images, name)
+        };
+
+        if let Some(size) = image.as_ref().ok().and_then(|image| images.get(image)) {
+            self.sizes.insert(resource.clone(), size.size());
         }
 
-        let mut coolasstexture: Handle<Image> = Handle::default();
+        let hires = self.get_namespaced(images, &resource, &[Namespace::HiRes]);
 
-        if self.map.texture_defs.contains_key(&name) {
-            // info!("Found {}", name);
-            match self.generate_image_from_texentry(images, self.map.texture_defs[&name].clone()) {
-                Ok(tex) => coolasstexture = tex,
-                Err(err) => {
-                    error!(err);
-                    return Handle::default();
+        match hires {
+            Some(Ok(hires)) => {
+                // without the original, the replacement is drawn at its own size
+                if let Some(size) = images.get(&hires) {
+                    self.sizes.entry(resource).or_insert(size.size());
                 }
+
+                Ok(hires)
             }
-        } else {
-            match self.get_patch(images, name.clone()) {
-                Ok(tex) => coolasstexture = tex,
-                Err(err) => {
-                    error!(err);
-                    return Handle::default();
-                }
+            _ => image,
+        }
+    }
+
+    /// size in map units of a loaded texture, flat or sprite, which a high
+    /// resolution replacement keeps from the image it replaces
+    pub fn world_size(&self, name: &str) -> Option<Vec2> {
+        self.sizes
+            .get(&name.replace("_flip", "").trim_end_matches('\0').to_uppercase())
+            .copied()
+    }
+
+    /// magenta and black checkerboard drawn where a texture or flat is missing
+    fn placeholder(
+        &mut self,
+        images: &mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+        flip: bool,
+    ) -> Handle<StandardMaterial> {
+        if let Some(material) = &self.placeholders[flip as usize] {
+            return material.clone();
+        }
+
+        let size = PLACEHOLDER_SQUARE * 8;
+        let mut data: Vec<u8> = Vec::with_capacity(size * size * 4);
+
+        for y in 0..size {
+            for x in 0..size {
+                let pixel: [u8; 4] = if (x / PLACEHOLDER_SQUARE + y / PLACEHOLDER_SQUARE).is_multiple_of(2) {
+                    [255, 0, 255, 255]
+                } else {
+                    [0, 0, 0, 255]
+                };
+                data.extend_from_slice(&pixel);
             }
         }
 
+        let ext: Extent3d = Extent3d {
+            width: size as u32,
+            height: size as u32,
+            ..default()
+        };
+
+        let mut image = Image::new_fill(
+            ext,
+            bevy::render::render_resource::TextureDimension::D2,
+            data.as_slice(),
+            TextureFormat::Rgba8Unorm,
+        );
+
+        let mut descriptor = ImageSampler::nearest_descriptor();
+        descriptor.address_mode_u = AddressMode::Repeat;
+        descriptor.address_mode_v = AddressMode::Repeat;
+        image.sampler_descriptor = ImageSampler::Descriptor(descriptor);
+
         let material = materials.add(StandardMaterial {
-            base_color_texture: Some(coolasstexture),
+            base_color_texture: Some(images.add(image)),
             cull_mode: if flip {
                 Some(Face::Front)
             } else {
@@ -798,47 +1012,69 @@ impl MapManager {
             ..Default::default()
         });
 
-        self.mat_map.insert(name, material.clone());
+        self.placeholders[flip as usize] = Some(material.clone());
 
-        return material;
+        material
     }
 
-    pub fn get_linedef_vector_as_vertices(&self, vec: &Vec<i16>) -> Vec<f64> {
-        let mut vertices: Vec<f64>;
-        vertices = Vec::new();
-        let mut vert_contains_list: Vec<Vec2>;
-        vert_contains_list = Vec::new();
+    pub fn get_texture(
+        &mut self,
+        mut images: &mut Assets<Image>,
+        mut materials: &mut Assets<StandardMaterial>,
+        mut name: String,
+        flip: bool,
+        masked: bool,
+    ) -> Handle<StandardMaterial> {
+        let resource = name.trim_end_matches('\0').to_uppercase();
 
-        for line_index in vec {
-            let linedef = &self.map.linedef_vec[*line_index as usize];
+        if self.missing.contains(&resource) {
+            return self.placeholder(images, materials, flip);
+        }
 
-            let vert1 = &self.map.vert_vec[linedef.start_vert as usize];
-            let vert2 = &self.map.vert_vec[linedef.end_vert as usize];
+        if flip {
+            name += "_flip";
+        }
 
-            let coords = vec![
-                vert1.x as f64,
-                vert1.y as f64,
-                vert2.x as f64,
-                vert2.y as f64,
-            ];
+        // the same texture drawn masked is another material
+        let key = if masked {
+            format!("{}_masked", name)
+        } else {
+            name.clone()
+        };
 
-            if !vert_contains_list.contains(&Vec2::new(coords[0] as f32, coords[1] as f32)) {
-                vert_contains_list.push(Vec2::new(coords[0] as f32, coords[1] as f32));
-            }
+        if self.mat_map.contains_key(&key) {
+            return self.mat_map[&key].clone();
+        }
 
-            if !vert_contains_list.contains(&Vec2::new(coords[2] as f32, coords[3] as f32)) {
-                vert_contains_list.push(Vec2::new(coords[2] as f32, coords[3] as f32));
+        let coolasstexture = match self.get_image(images, name.clone()) {
+            Ok(tex) => tex,
+            Err(err) => {
+                error!(err);
+                self.missing.insert(resource);
+                return self.placeholder(images, materials, flip);
             }
-        }
+        };
 
-        // print!("\n");
+        // see-through pixels of grates and fences on two-sided middle textures
+        // are cut out, everything else is drawn opaque like vanilla
+        let material = materials.add(StandardMaterial {
+            base_color_texture: Some(coolasstexture),
+            alpha_mode: if masked {
+                AlphaMode::Mask(0.5)
+            } else {
+                AlphaMode::Opaque
+            },
+            cull_mode: if flip {
+                Some(Face::Front)
+            } else {
+                Some(Face::Back)
+            },
+            unlit: true,
+            ..Default::default()
+        });
 
-        for vec in vert_contains_list {
-            // println!("({}, {})", vec.x, vec.y);
-            vertices.push(vec.x as f64);
-            vertices.push(vec.y as f64);
-        }
+        self.mat_map.insert(key, material.clone());
 
-        return vertices;
+        return material;
     }
 }
@@ -0,0 +1,385 @@
+use bevy::prelude::*;
+use bevy_earcutr::{EarcutrInput, PolygonMeshBuilder};
+
+use super::alignment::{anchor, Anchor};
+use super::complete_map::{Sector, Sidedef, Vert};
+use super::components::{FlatSurface, Side, WallPart, WallSurface};
+use super::MapManager;
+use crate::lighting::LitSurface;
+use crate::sky::is_sky_flat;
+
+/// one part of a sidedef between two heights, as `generate_wall` takes it
+struct WallQuad {
+    start: Vert,
+    end: Vert,
+    floor_height: i16,
+    ceiling_height: i16,
+    sidedef: Sidedef,
+    anchor: Anchor,
+    masked: bool,
+    surface: WallSurface,
+}
+
+fn point_inside_aabb(aabb_min: Vec2, aabb_max: Vec2, point: Vert) -> bool {
+    point.x > aabb_min.x as i16
+        && point.x < aabb_max.x as i16
+        && point.y > aabb_min.y as i16
+        && point.y < aabb_max.y as i16
+}
+
+impl MapManager {
+    /// fills `Sector::linedefs` with the lines bounding every sector, lines
+    /// with a vertex, sidedef or sector out of range are left for the lint to report
+    pub fn link_sector_linedefs(&mut self) {
+        for i in 0..self.map.linedef_vec.len() {
+            let ((Some(front), back), Some(_)) = (self.map.line_sectors(i), self.map.line_ends(i)) else {
+                continue;
+            };
+
+            match back {
+                Some(back) if back != front => {
+                    self.map.sector_vec[front].linedefs.push(i as i16);
+                    self.map.sector_vec[back].linedefs.push(i as i16);
+                }
+                Some(_) => {}
+                None => self.map.sector_vec[front].linedefs.push(i as i16),
+            }
+        }
+    }
+
+    /// upper, lower and middle walls of a linedef at the current sector heights
+    pub fn spawn_linedef_walls(
+        &mut self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        images: &mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+        i: usize,
+    ) {
+        for quad in self.linedef_walls(i) {
+            self.generate_wall(
+                commands,
+                meshes,
+                images,
+                materials,
+                &quad.start,
+                &quad.end,
+                quad.floor_height,
+                quad.ceiling_height,
+                &quad.sidedef,
+                quad.anchor,
+                quad.masked,
+                quad.surface,
+            );
+        }
+    }
+
+    /// meshes of the walls of a linedef at the current sector heights, the
+    /// ones a moving sector swaps into the entities it already has
+    pub fn linedef_wall_meshes(&self, i: usize) -> Vec<(WallSurface, Mesh)> {
+        self.linedef_walls(i)
+            .into_iter()
+            .filter_map(|quad| {
+                let mesh = self.wall_mesh(
+                    &quad.start,
+                    &quad.end,
+                    quad.floor_height,
+                    quad.ceiling_height,
+                    &quad.sidedef,
+                    quad.anchor,
+                    quad.masked,
+                    quad.surface.part,
+                )?;
+                Some((quad.surface, mesh))
+            })
+            .collect()
+    }
+
+    /// parts of both sides of a linedef that have a wall between their heights
+    fn linedef_walls(&self, i: usize) -> Vec<WallQuad> {
+        let mut quads: Vec<WallQuad> = Vec::new();
+        let linedef = &self.map.linedef_vec[i];
+        let (front, back) = self.map.line_sectors(i);
+
+        let (Some(_), Some(vert1), Some(vert2)) = (
+            front,
+            self.map.vert_vec.get(linedef.start_vert as usize),
+            self.map.vert_vec.get(linedef.end_vert as usize),
+        ) else {
+            return quads;
+        };
+
+        for (side, this_sidedef, this, other) in [
+            (Side::Front, linedef.front_sidedef, front, back),
+            (Side::Back, linedef.back_sidedef, back, front),
+        ] {
+            // a side with a sector has a sidedef in range
+            let Some(this) = this else {
+                continue;
+            };
+
+            let sidedef = &self.map.sidefef_vec[this_sidedef as usize];
+            let this = &self.map.sector_vec[this];
+            let other = other.map(|other| &self.map.sector_vec[other]);
+
+            // the back side runs the other way along the line
+            let (start, end) = match side {
+                Side::Front => (vert1, vert2),
+                Side::Back => (vert2, vert1),
+            };
+
+            let mut wall = |part: WallPart, floor_height: i16, ceiling_height: i16, masked: bool| {
+                quads.push(WallQuad {
+                    start: start.clone(),
+                    end: end.clone(),
+                    floor_height,
+                    ceiling_height,
+                    sidedef: sidedef.clone(),
+                    anchor: anchor(part, linedef.flags, this, other),
+                    masked,
+                    surface: WallSurface {
+                        linedef: i,
+                        side,
+                        part,
+                    },
+                });
+            };
+
+            let Some(other) = other else {
+                wall(WallPart::Middle, this.floor_height, this.ceil_height, false);
+                continue;
+            };
+
+            // vanilla never draws upper textures between two sky ceilings
+            let sky_hack = is_sky_flat(&this.ceil_tex) && is_sky_flat(&other.ceil_tex);
+
+            if this.ceil_height > other.ceil_height && !sky_hack {
+                wall(WallPart::Upper, other.ceil_height, this.ceil_height, false);
+            }
+
+            if this.floor_height < other.floor_height {
+                wall(WallPart::Lower, this.floor_height, other.floor_height, false);
+            }
+
+            wall(
+                WallPart::Middle,
+                this.floor_height.max(other.floor_height),
+                this.ceil_height.min(other.ceil_height),
+                true,
+            );
+        }
+
+        quads
+    }
+
+    /// floor and ceiling polygons of a sector at its current heights
+    pub fn spawn_sector_flats(
+        &mut self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        images: &mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+        sector_ind: usize,
+    ) {
+        let sector = self.map.sector_vec[sector_ind].clone();
+        let shapes = self.map.detect_shapes(&sector);
+
+        if shapes.len() <= 0
+        {
+            return;
+        }
+
+        debug!("Detected shapes: {}", shapes.len());
+
+        let mut biggest_area = f32::MIN;
+        let mut biggest_aabb_index = 0;
+
+        let mut holes: Vec<Vec<f64>>;
+        holes = Vec::new();
+
+        let mut aabb_min = Vec2::new(-1., -1.);
+        let mut aabb_max = Vec2::new(-1., -1.);
+
+        for (j, shape) in shapes.clone().into_iter().enumerate() {
+            let mut min_x = f32::MAX;
+            let mut max_x = f32::MIN;
+            let mut min_y = f32::MAX;
+            let mut max_y = f32::MIN;
+
+            let shape_vec = self.map.get_linedef_vector_as_vertices(&shape);
+
+            holes.push(shape_vec);
+
+            for i in shape {
+                let linedef = &self.map.linedef_vec[i as usize];
+                let vert1 = &self.map.vert_vec[linedef.start_vert as usize];
+                let vert2 = &self.map.vert_vec[linedef.end_vert as usize];
+
+                let x1 = vert1.x as f32;
+                let y1 = vert1.y as f32;
+                let x2 = vert2.x as f32;
+                let y2 = vert2.y as f32;
+
+                min_x = min_x.min(x1).min(x2);
+                max_x = max_x.max(x1).max(x2);
+                min_y = min_y.min(y1).min(y2);
+                max_y = max_y.max(y1).max(y2);
+            }
+
+            let _aabb_min = Vec2::new(min_x, min_y);
+            let _aabb_max = Vec2::new(max_x, max_y);
+
+            let area = (_aabb_max.x - _aabb_min.x) * (_aabb_max.y - _aabb_min.y);
+
+            if area > biggest_area {
+                biggest_area = area;
+                biggest_aabb_index = j;
+                aabb_max = _aabb_max;
+                aabb_min = _aabb_min;
+            }
+        }
+
+        let mut floor_vertices: Vec<f64>;
+        floor_vertices = holes[biggest_aabb_index].clone();
+
+        for (j, shape) in shapes.clone().into_iter().enumerate() {
+            if j == biggest_aabb_index {
+                continue;
+            }
+
+            let mut inside: bool;
+            for index in shape.clone() {
+                let line = &self.map.linedef_vec[index as usize];
+                let s_vert = &self.map.vert_vec[line.start_vert as usize];
+                let e_vert = &self.map.vert_vec[line.start_vert as usize];
+
+                inside = point_inside_aabb(aabb_min, aabb_max, s_vert.clone());
+
+                inside = inside && point_inside_aabb(aabb_min, aabb_max, e_vert.clone());
+
+                if !inside {
+                    let mut shape_vec = self.map.get_linedef_vector_as_vertices(&shape);
+                    self.spawn_flat(commands, &mut shape_vec, Vec::new(), meshes, images, materials, &sector, sector_ind);
+                    if let Some(index) = holes.iter().position(|x| x == &shape_vec) {
+                        holes.remove(index);
+                    }
+                    break;
+                }
+            }
+        }
+
+        let shape_vec = self.map.get_linedef_vector_as_vertices(&shapes[biggest_aabb_index]);
+        if let Some(index) = holes.iter().position(|x| x == &shape_vec) {
+            holes.remove(index);
+        }
+
+        self.spawn_flat(commands, &mut floor_vertices, holes, meshes, images, materials, &sector, sector_ind);
+    }
+
+    fn spawn_flat(
+        &mut self,
+        commands: &mut Commands,
+        floor_vertices: &mut Vec<f64>,
+        holes: Vec<Vec<f64>>,
+        meshes: &mut Assets<Mesh>,
+        images: &mut Assets<Image>,
+        materials: &mut Assets<StandardMaterial>,
+        sector: &Sector,
+        sector_ind: usize,
+    ) {
+        let mesh_floor: Option<Mesh>;
+
+        if holes.len() > 0 {
+            mesh_floor = MapManager::triangulate_polygon_with_holes(floor_vertices, &holes);
+        } else {
+            let mut builder = PolygonMeshBuilder::default();
+
+            builder.add_earcutr_input(EarcutrInput {
+                vertices: floor_vertices.clone(),
+                interior_indices: Vec::new(),
+            });
+
+            mesh_floor = builder.build();
+        }
+
+        match mesh_floor {
+            Some(_) => {
+                let actual_mesh = mesh_floor.unwrap();
+
+                // flats repeat every image size along the map axes
+                let flat_mesh = |size: Option<Vec2>| {
+                    let size = size.unwrap_or(Vec2::splat(64.));
+                    let uvs: Vec<Vec2> = floor_vertices
+                        .chunks_exact(2)
+                        .map(|point| Vec2::new(point[0] as f32, point[1] as f32) / size)
+                        .collect();
+
+                    let mut mesh = actual_mesh.clone();
+                    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+                    mesh
+                };
+
+                let floor_name = std::str::from_utf8(&sector.floor_tex).unwrap().to_string();
+                let floor_material = self.get_texture(images, materials, floor_name.clone(), false, false);
+
+                commands
+                    .spawn(PbrBundle {
+                        mesh: meshes.add(flat_mesh(self.world_size(&floor_name))),
+                        material: floor_material,
+                        ..default()
+                    })
+                    .insert(LitSurface { sector: sector_ind })
+                    .insert(FlatSurface {
+                        sector: sector_ind,
+                        is_ceiling: false,
+                    })
+                    .insert(Transform {
+                        translation: Vec3 {
+                            x: 0.,
+                            y: sector.floor_height as f32,
+                            z: 0.,
+                        },
+                        rotation: Quat::from_rotation_x(-90.0f32.to_radians())
+                            * Quat::from_rotation_z(180.0f32.to_radians()),
+                        ..Default::default()
+                    });
+
+                let mut ceiling = if is_sky_flat(&sector.ceil_tex) {
+                    commands.spawn(MaterialMeshBundle {
+                        mesh: meshes.add(flat_mesh(None)),
+                        material: self.sky_material.clone(),
+                        ..default()
+                    })
+                } else {
+                    let ceiling_name = std::str::from_utf8(&sector.ceil_tex).unwrap().to_string();
+                    let ceiling_material = self.get_texture(images, materials, ceiling_name.clone(), true, false);
+
+                    let mut ceiling = commands.spawn(PbrBundle {
+                        mesh: meshes.add(flat_mesh(self.world_size(&ceiling_name))),
+                        material: ceiling_material,
+                        ..default()
+                    });
+                    ceiling.insert(LitSurface { sector: sector_ind });
+                    ceiling
+                };
+
+                ceiling
+                    .insert(FlatSurface {
+                        sector: sector_ind,
+                        is_ceiling: true,
+                    })
+                    .insert(Transform {
+                        translation: Vec3 {
+                            x: 0.,
+                            y: sector.ceil_height as f32,
+                            z: 0.,
+                        },
+                        rotation: Quat::from_rotation_x(-90.0f32.to_radians())
+                            * Quat::from_rotation_z(180.0f32.to_radians()),
+                        ..Default::default()
+                    });
+            }
+            None => println!("Merda culo"),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use crate::mapmanager::complete_map::trimmed;
+use crate::mapmanager::MapManager;
+use crate::panel::{cleanup_panel, panel_text_bundle, toggle_panel};
+use crate::state::GameState;
+
+/// indices listed per missing name before the rest is cut off
+const LISTED: usize = 16;
+
+/// a texture or flat that could not be found and what uses it
+pub struct MissingResource {
+    pub name: String,
+    pub sidedefs: Vec<usize>,
+    pub sectors: Vec<usize>,
+}
+
+/// the missing names of the loaded map with the sidedefs and sectors using them
+pub fn missing_resources(manager: &MapManager) -> Vec<MissingResource> {
+    let map = &manager.map;
+    let uses = |name: &[u8; 8], missing: &str| trimmed(name).to_uppercase() == missing;
+
+    manager
+        .missing
+        .iter()
+        .map(|missing| MissingResource {
+            name: missing.clone(),
+            sidedefs: map
+                .sidefef_vec
+                .iter()
+                .enumerate()
+                .filter(|(_, side)| {
+                    [&side.upper_tex, &side.mid_tex, &side.lower_tex]
+                        .into_iter()
+                        .any(|name| uses(name, missing))
+                })
+                .map(|(i, _)| i)
+                .collect(),
+            sectors: map
+                .sector_vec
+                .iter()
+                .enumerate()
+                .filter(|(_, sector)| uses(&sector.floor_tex, missing) || uses(&sector.ceil_tex, missing))
+                .map(|(i, _)| i)
+                .collect(),
+        })
+        .collect()
+}
+
+fn index_list(indices: &[usize]) -> String {
+    let mut list: Vec<String> = indices.iter().take(LISTED).map(usize::to_string).collect();
+
+    if indices.len() > LISTED {
+        list.push(format!("and {} more", indices.len() - LISTED));
+    }
+
+    list.join(" ")
+}
+
+fn panel_text(missing: &[MissingResource]) -> String {
+    if missing.is_empty() {
+        return "No missing textures or flats".to_string();
+    }
+
+    let mut text = format!("{} missing textures and flats", missing.len());
+
+    for resource in missing {
+        text += &format!("\n{}", resource.name);
+
+        if !resource.sidedefs.is_empty() {
+            text += &format!("\n  Sidedefs {}", index_list(&resource.sidedefs));
+        }
+
+        if !resource.sectors.is_empty() {
+            text += &format!("\n  Sectors {}", index_list(&resource.sectors));
+        }
+    }
+
+    text
+}
+
+#[derive(Component)]
+struct MissingPanel;
+
+/// T lists the textures and flats drawn with the checkerboard placeholder
+fn toggle_missing(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    manager: Res<MapManager>,
+    mut panels: Query<&mut Style, With<MissingPanel>>,
+) {
+    // the map picker, inspector, stats and lint panels hold the corners
+    let anchor = UiRect {
+        left: Val::Percent(30.),
+        top: Val::Px(10.),
+        ..default()
+    };
+
+    let Some(panel) = toggle_panel(&mut commands, &keys, KeyCode::T, anchor, MissingPanel, &mut panels) else {
+        return;
+    };
+
+    let text = panel_text(&missing_resources(&manager));
+
+    commands.entity(panel).with_children(|panel| {
+        panel.spawn(panel_text_bundle(&asset_server, text));
+    });
+}
+
+pub struct MissingPlugin;
+
+impl Plugin for MissingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            toggle_missing
+                .run_if(resource_exists::<MapManager>())
+                .in_set(OnUpdate(GameState::MapView)),
+        )
+        .add_system(cleanup_panel::<MissingPanel>.in_schedule(OnExit(GameState::MapView)));
+    }
+}
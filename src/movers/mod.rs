@@ -0,0 +1,1076 @@
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+use crate::mapmanager::complete_map::{CompleteMap, Sector, Sidedef};
+use crate::mapmanager::components::{FlatSurface, WallSurface};
+use crate::mapmanager::MapManager;
+use crate::specials::{
+    decode, Action, CeilingTarget, Change, ChangeModel, DoorKind, FloorTarget, PlatKind, Trigger,
+    TypeChange, FLOORSPEED,
+};
+use crate::scrolling::{run_scrollers, Scrollers};
+use crate::state::GameState;
+use crate::tic::TicClock;
+
+/// How the player set a line off
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Activation {
+    Cross,
+    Use,
+    Shoot,
+}
+
+/// Plane a thinker moves, Boom keeps one thinker per plane like its
+/// `floordata` and `ceilingdata` so a sector can run a floor and a ceiling
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Plane {
+    Floor,
+    Ceiling,
+}
+
+/// Sector thinkers
+enum Mover {
+    Door {
+        kind: DoorKind,
+        height: f32,
+        top: f32,
+        speed: f32,
+        direction: i8,
+        /// tics spent open, or shut for close-wait-open doors
+        wait: u32,
+        countdown: u32,
+    },
+    Plat {
+        kind: PlatKind,
+        height: f32,
+        low: f32,
+        high: f32,
+        speed: f32,
+        direction: i8,
+        old_direction: i8,
+        wait: u32,
+        countdown: u32,
+        in_stasis: bool,
+    },
+    Floor {
+        height: f32,
+        dest: f32,
+        speed: f32,
+        /// floor texture applied once the destination is reached
+        texture: Option<[u8; 8]>,
+        /// sector type applied along with the texture
+        special: Option<i16>,
+    },
+    Ceiling {
+        height: f32,
+        bottom: f32,
+        top: f32,
+        speed: f32,
+        direction: i8,
+        crusher: bool,
+        in_stasis: bool,
+    },
+}
+
+/// T_MovePlane without things to crush, true once `dest` is reached
+fn move_plane(height: &mut f32, dest: f32, speed: f32) -> bool {
+    if *height < dest {
+        *height = (*height + speed).min(dest);
+    } else {
+        *height = (*height - speed).max(dest);
+    }
+
+    *height == dest
+}
+
+impl Mover {
+    fn plane(&self) -> Plane {
+        match self {
+            Mover::Plat { .. } | Mover::Floor { .. } => Plane::Floor,
+            Mover::Door { .. } | Mover::Ceiling { .. } => Plane::Ceiling,
+        }
+    }
+
+    /// advance one tic, true when the thinker is done
+    fn tick(&mut self, sector: &mut Sector) -> bool {
+        match self {
+            Mover::Door {
+                kind,
+                height,
+                top,
+                speed,
+                direction,
+                wait,
+                countdown,
+            } => {
+                let finished = match *direction {
+                    0 => {
+                        *countdown = countdown.saturating_sub(1);
+                        if *countdown == 0 {
+                            *direction = if *kind == DoorKind::CloseWaitOpen { 1 } else { -1 };
+                        }
+                        false
+                    }
+                    -1 => {
+                        if move_plane(height, sector.floor_height as f32, *speed) {
+                            if *kind == DoorKind::CloseWaitOpen {
+                                *direction = 0;
+                                *countdown = *wait;
+                                false
+                            } else {
+                                true
+                            }
+                        } else {
+                            false
+                        }
+                    }
+                    _ => {
+                        if move_plane(height, *top, *speed) {
+                            if *kind == DoorKind::OpenWaitClose {
+                                *direction = 0;
+                                *countdown = *wait;
+                                false
+                            } else {
+                                true
+                            }
+                        } else {
+                            false
+                        }
+                    }
+                };
+
+                sector.ceil_height = height.round() as i16;
+                finished
+            }
+            Mover::Plat {
+                kind,
+                height,
+                low,
+                high,
+                speed,
+                direction,
+                old_direction,
+                wait,
+                countdown,
+                in_stasis,
+            } => {
+                if *in_stasis {
+                    return false;
+                }
+
+                let finished = match *direction {
+                    0 => {
+                        *countdown = countdown.saturating_sub(1);
+                        if *countdown == 0 {
+                            *direction = -*old_direction;
+                        }
+                        false
+                    }
+                    -1 => {
+                        if move_plane(height, *low, *speed) {
+                            *old_direction = -1;
+                            *direction = 0;
+                            *countdown = *wait;
+                        }
+                        false
+                    }
+                    _ => {
+                        if move_plane(height, *high, *speed) {
+                            *old_direction = 1;
+                            *direction = 0;
+                            *countdown = *wait;
+                            *kind != PlatKind::Perpetual
+                        } else {
+                            false
+                        }
+                    }
+                };
+
+                sector.floor_height = height.round() as i16;
+                finished
+            }
+            Mover::Floor {
+                height,
+                dest,
+                speed,
+                texture,
+                special,
+            } => {
+                let finished = move_plane(height, *dest, *speed);
+
+                sector.floor_height = height.round() as i16;
+
+                if finished {
+                    if let Some(texture) = texture {
+                        sector.floor_tex = *texture;
+                    }
+                    if let Some(special) = special {
+                        sector.special = *special;
+                    }
+                }
+
+                finished
+            }
+            Mover::Ceiling {
+                height,
+                bottom,
+                top,
+                speed,
+                direction,
+                crusher,
+                in_stasis,
+            } => {
+                if *in_stasis {
+                    return false;
+                }
+
+                let dest = if *direction < 0 { *bottom } else { *top };
+                let mut finished = false;
+
+                if move_plane(height, dest, *speed) {
+                    if *crusher {
+                        *direction = -*direction;
+                    } else {
+                        finished = true;
+                    }
+                }
+
+                sector.ceil_height = height.round() as i16;
+                finished
+            }
+        }
+    }
+}
+
+fn lowest_ceiling_surrounding(map: &CompleteMap, sector: usize) -> i16 {
+    map.sector_neighbours(sector)
+        .iter()
+        .map(|&n| map.sector_vec[n].ceil_height)
+        .min()
+        .unwrap_or(map.sector_vec[sector].ceil_height)
+}
+
+fn highest_ceiling_surrounding(map: &CompleteMap, sector: usize) -> i16 {
+    map.sector_neighbours(sector)
+        .iter()
+        .map(|&n| map.sector_vec[n].ceil_height)
+        .max()
+        .unwrap_or(map.sector_vec[sector].ceil_height)
+}
+
+/// the sector's own floor counts, as in P_FindLowestFloorSurrounding
+fn lowest_floor_surrounding(map: &CompleteMap, sector: usize) -> i16 {
+    map.sector_neighbours(sector)
+        .iter()
+        .map(|&n| map.sector_vec[n].floor_height)
+        .fold(map.sector_vec[sector].floor_height, i16::min)
+}
+
+fn highest_floor_surrounding(map: &CompleteMap, sector: usize) -> i16 {
+    map.sector_neighbours(sector)
+        .iter()
+        .map(|&n| map.sector_vec[n].floor_height)
+        .fold(-500, i16::max)
+}
+
+fn floor_of(sector: &Sector) -> i16 {
+    sector.floor_height
+}
+
+fn ceiling_of(sector: &Sector) -> i16 {
+    sector.ceil_height
+}
+
+/// nearest neighbouring plane above (or below) the sector's own, P_FindNextHighestFloor and co.
+fn next_plane(map: &CompleteMap, sector: usize, plane: fn(&Sector) -> i16, up: bool) -> i16 {
+    let current = plane(&map.sector_vec[sector]);
+    let heights = map
+        .sector_neighbours(sector)
+        .into_iter()
+        .map(|n| plane(&map.sector_vec[n]));
+
+    if up {
+        heights.filter(|&height| height > current).min()
+    } else {
+        heights.filter(|&height| height < current).max()
+    }
+    .unwrap_or(current)
+}
+
+fn lower_tex(side: &Sidedef) -> [u8; 8] {
+    side.lower_tex
+}
+
+fn upper_tex(side: &Sidedef) -> [u8; 8] {
+    side.upper_tex
+}
+
+/// shortest lower (or upper) texture on the two-sided lines around a sector
+fn shortest_texture(map: &CompleteMap, sector: usize, texture: fn(&Sidedef) -> [u8; 8]) -> i16 {
+    let mut shortest = i16::MAX;
+
+    for &line in &map.sector_vec[sector].linedefs {
+        let linedef = &map.linedef_vec[line as usize];
+
+        // both sidedefs are in range when both sectors are
+        let (Some(_), Some(_)) = map.line_sectors(line as usize) else {
+            continue;
+        };
+
+        for side in [linedef.front_sidedef, linedef.back_sidedef] {
+            let name = std::str::from_utf8(&texture(&map.sidefef_vec[side as usize]))
+                .unwrap_or_default()
+                .to_string();
+
+            if let Some(entry) = map.texture_defs.get(&name) {
+                shortest = shortest.min(entry.height);
+            }
+        }
+    }
+
+    shortest
+}
+
+/// the sector type a texture change leaves behind
+fn changed_type(change: TypeChange, model: &Sector) -> Option<i16> {
+    match change {
+        TypeChange::Keep => None,
+        TypeChange::Zero => Some(0),
+        TypeChange::Copy => Some(model.special),
+    }
+}
+
+/// neighbour whose plane sits at `height`, P_FindModelFloorSector
+fn model_sector(map: &CompleteMap, sector: usize, plane: fn(&Sector) -> i16, height: i16) -> Option<usize> {
+    map.sector_neighbours(sector)
+        .into_iter()
+        .find(|&n| plane(&map.sector_vec[n]) == height)
+}
+
+#[derive(Resource, Default)]
+pub struct SectorMovers {
+    movers: HashMap<(usize, Plane), Mover>,
+    /// sectors whose textures changed since the last rebuild
+    dirty: HashSet<usize>,
+    /// sectors whose heights changed since the last rebuild
+    moved: HashSet<usize>,
+}
+
+impl SectorMovers {
+    /// run the special of `line`, returns whether anything started moving
+    pub fn activate(
+        &mut self,
+        map: &mut CompleteMap,
+        line: usize,
+        activation: Activation,
+        from_front: bool,
+    ) -> bool {
+        let Some(special) = decode(map.linedef_vec[line].special_type) else {
+            return false;
+        };
+
+        let matches = match special.trigger {
+            Trigger::Walk => activation == Activation::Cross,
+            Trigger::Switch | Trigger::Manual => activation == Activation::Use && from_front,
+            Trigger::Gun => activation == Activation::Shoot,
+            Trigger::Passive => false,
+        };
+
+        if !matches {
+            return false;
+        }
+
+        let started = match (special.trigger, special.action) {
+            (Trigger::Manual, Action::Door { .. }) => {
+                self.manual_door(map, line, special.repeatable, special.action)
+            }
+            (trigger, action) => self.run_action(map, line, action, trigger == Trigger::Manual),
+        };
+
+        // switches stay usable until they work, walk and gun lines fire once
+        let spent = started || matches!(special.trigger, Trigger::Walk | Trigger::Gun);
+
+        if spent && !special.repeatable {
+            map.linedef_vec[line].special_type = 0;
+        }
+
+        started
+    }
+
+    /// EV_VerticalDoor, acts on the sector behind the line
+    fn manual_door(&mut self, map: &CompleteMap, line: usize, repeatable: bool, action: Action) -> bool {
+        let (_, Some(sector)) = map.line_sectors(line) else {
+            return false;
+        };
+
+        let Action::Door { kind, speed, wait, .. } = action else {
+            return false;
+        };
+
+        if let Some(mover) = self.movers.get_mut(&(sector, Plane::Ceiling)) {
+            // pushing a moving door again reverses it
+            if let Mover::Door { direction, .. } = mover {
+                if repeatable {
+                    *direction = if *direction == -1 { 1 } else { -1 };
+                    return true;
+                }
+            }
+
+            return false;
+        }
+
+        self.start_door(map, sector, kind, speed, wait);
+        true
+    }
+
+    fn start_door(&mut self, map: &CompleteMap, sector: usize, kind: DoorKind, speed: f32, wait: u32) {
+        let height = map.sector_vec[sector].ceil_height as f32;
+
+        let (top, direction) = match kind {
+            DoorKind::Close | DoorKind::CloseWaitOpen => (height, -1),
+            DoorKind::Open | DoorKind::OpenWaitClose => {
+                ((lowest_ceiling_surrounding(map, sector) - 4) as f32, 1)
+            }
+        };
+
+        self.start(
+            sector,
+            Mover::Door {
+                kind,
+                height,
+                top,
+                speed,
+                direction,
+                wait,
+                countdown: 0,
+            },
+        );
+    }
+
+    /// attach a thinker to the plane of `sector` it moves
+    fn start(&mut self, sector: usize, mover: Mover) {
+        self.movers.insert((sector, mover.plane()), mover);
+    }
+
+    /// sectors tagged by `line`, or the one behind it for manual specials,
+    /// that have no thinker on `plane` yet
+    fn idle_sectors(&self, map: &CompleteMap, line: usize, manual: bool, plane: Plane) -> Vec<usize> {
+        let sectors = if manual {
+            map.line_sectors(line).1.into_iter().collect()
+        } else {
+            map.tagged_sectors(map.linedef_vec[line].sector_tag)
+        };
+
+        sectors
+            .into_iter()
+            .filter(|&sector| !self.movers.contains_key(&(sector, plane)))
+            .collect()
+    }
+
+    fn run_action(&mut self, map: &mut CompleteMap, line: usize, action: Action, manual: bool) -> bool {
+        let tag = map.linedef_vec[line].sector_tag;
+        let trigger_sector = map.line_sectors(line).0;
+        let mut started = false;
+
+        match action {
+            Action::Door { kind, speed, wait, .. } => {
+                for sector in self.idle_sectors(map, line, manual, Plane::Ceiling) {
+                    self.start_door(map, sector, kind, speed, wait);
+                    started = true;
+                }
+            }
+            Action::Plat {
+                kind: PlatKind::Toggle,
+                ..
+            } => {
+                info!("Line {} toggle lift is not simulated", line);
+            }
+            Action::Plat { kind, speed, wait } => {
+                if kind == PlatKind::Perpetual {
+                    started |= self.set_stasis(map, tag, false);
+                }
+
+                for sector in self.idle_sectors(map, line, manual, Plane::Floor) {
+                    let floor = map.sector_vec[sector].floor_height;
+
+                    let (low, high, direction) = match kind {
+                        PlatKind::DownWaitUp => {
+                            (lowest_floor_surrounding(map, sector).min(floor), floor, -1)
+                        }
+                        PlatKind::DownToNextLowerWaitUp => {
+                            (next_plane(map, sector, floor_of, false), floor, -1)
+                        }
+                        PlatKind::DownToLowestCeilingWaitUp => {
+                            (lowest_ceiling_surrounding(map, sector).min(floor), floor, -1)
+                        }
+                        PlatKind::Perpetual | PlatKind::Toggle => (
+                            lowest_floor_surrounding(map, sector).min(floor),
+                            highest_floor_surrounding(map, sector).max(floor),
+                            -1,
+                        ),
+                        PlatKind::RaiseToNearestAndChange => {
+                            (floor, next_plane(map, sector, floor_of, true), 1)
+                        }
+                        PlatKind::RaiseAndChange(amount) => (floor, floor + amount, 1),
+                    };
+
+                    if matches!(
+                        kind,
+                        PlatKind::RaiseToNearestAndChange | PlatKind::RaiseAndChange(_)
+                    ) {
+                        if let Some(model) = trigger_sector {
+                            map.sector_vec[sector].floor_tex = map.sector_vec[model].floor_tex;
+                            self.dirty.insert(sector);
+                        }
+                    }
+
+                    self.start(
+                        sector,
+                        Mover::Plat {
+                            kind,
+                            height: floor as f32,
+                            low: low as f32,
+                            high: high as f32,
+                            speed,
+                            direction,
+                            old_direction: direction,
+                            wait,
+                            countdown: 0,
+                            in_stasis: false,
+                        },
+                    );
+                    started = true;
+                }
+            }
+            Action::StopPlat => started = self.set_stasis(map, tag, true),
+            Action::Floor {
+                target,
+                speed,
+                change,
+                ..
+            } => {
+                for sector in self.idle_sectors(map, line, manual, Plane::Floor) {
+                    let floor = map.sector_vec[sector].floor_height;
+                    let ceiling = map.sector_vec[sector].ceil_height;
+
+                    let dest = match target {
+                        FloorTarget::LowestCeiling => {
+                            lowest_ceiling_surrounding(map, sector).min(ceiling)
+                        }
+                        FloorTarget::LowestCeilingMinus8 => {
+                            lowest_ceiling_surrounding(map, sector).min(ceiling) - 8
+                        }
+                        FloorTarget::NextHigher => next_plane(map, sector, floor_of, true),
+                        FloorTarget::NextLower => next_plane(map, sector, floor_of, false),
+                        FloorTarget::LowestFloor => lowest_floor_surrounding(map, sector),
+                        FloorTarget::HighestFloor => highest_floor_surrounding(map, sector),
+                        FloorTarget::HighestFloorPlus8 => {
+                            let highest = highest_floor_surrounding(map, sector);
+                            if highest != floor {
+                                highest + 8
+                            } else {
+                                highest
+                            }
+                        }
+                        FloorTarget::Ceiling => ceiling,
+                        FloorTarget::By(amount) => floor.saturating_add(amount),
+                        FloorTarget::ShortestLowerTexture => {
+                            floor.saturating_add(shortest_texture(map, sector, lower_tex))
+                        }
+                    };
+
+                    let (texture, special) = match change {
+                        Some(Change {
+                            model: ChangeModel::Trigger,
+                            sector_type,
+                        }) => {
+                            if let Some(model) = trigger_sector {
+                                let special = changed_type(sector_type, &map.sector_vec[model]);
+                                map.sector_vec[sector].floor_tex = map.sector_vec[model].floor_tex;
+                                if let Some(special) = special {
+                                    map.sector_vec[sector].special = special;
+                                }
+                                self.dirty.insert(sector);
+                            }
+                            (None, None)
+                        }
+                        Some(Change {
+                            model: ChangeModel::Numeric,
+                            sector_type,
+                        }) => match model_sector(map, sector, floor_of, dest) {
+                            Some(model) => (
+                                Some(map.sector_vec[model].floor_tex),
+                                changed_type(sector_type, &map.sector_vec[model]),
+                            ),
+                            None => (None, None),
+                        },
+                        None => (None, None),
+                    };
+
+                    self.start(
+                        sector,
+                        Mover::Floor {
+                            height: floor as f32,
+                            dest: dest as f32,
+                            speed,
+                            texture,
+                            special,
+                        },
+                    );
+                    started = true;
+                }
+            }
+            Action::Ceiling {
+                target,
+                up,
+                speed,
+                change,
+                ..
+            } => {
+                for sector in self.idle_sectors(map, line, manual, Plane::Ceiling) {
+                    let floor = map.sector_vec[sector].floor_height;
+                    let ceiling = map.sector_vec[sector].ceil_height;
+
+                    let dest = match target {
+                        CeilingTarget::Floor => floor,
+                        CeilingTarget::FloorPlus8 => floor + 8,
+                        CeilingTarget::HighestCeiling => highest_ceiling_surrounding(map, sector),
+                        CeilingTarget::LowestCeiling => lowest_ceiling_surrounding(map, sector),
+                        CeilingTarget::NextHigher => next_plane(map, sector, ceiling_of, true),
+                        CeilingTarget::NextLower => next_plane(map, sector, ceiling_of, false),
+                        CeilingTarget::HighestFloor => highest_floor_surrounding(map, sector),
+                        CeilingTarget::By(amount) => ceiling.saturating_add(amount),
+                        CeilingTarget::ShortestUpperTexture => {
+                            ceiling.saturating_add(shortest_texture(map, sector, upper_tex))
+                        }
+                    };
+
+                    let (bottom, top, direction) = if up {
+                        (floor, dest, 1)
+                    } else {
+                        (dest, ceiling, -1)
+                    };
+
+                    // ceiling textures change straight away, whatever the model
+                    if let Some(Change { model, sector_type }) = change {
+                        let model = match model {
+                            ChangeModel::Trigger => trigger_sector,
+                            ChangeModel::Numeric => model_sector(map, sector, ceiling_of, dest),
+                        };
+
+                        if let Some(model) = model {
+                            let special = changed_type(sector_type, &map.sector_vec[model]);
+                            map.sector_vec[sector].ceil_tex = map.sector_vec[model].ceil_tex;
+                            if let Some(special) = special {
+                                map.sector_vec[sector].special = special;
+                            }
+                            self.dirty.insert(sector);
+                        }
+                    }
+
+                    self.start(
+                        sector,
+                        Mover::Ceiling {
+                            height: ceiling as f32,
+                            bottom: bottom as f32,
+                            top: top as f32,
+                            speed,
+                            direction,
+                            crusher: false,
+                            in_stasis: false,
+                        },
+                    );
+                    started = true;
+                }
+            }
+            Action::RaiseCeilingLowerFloor {
+                ceiling,
+                floor,
+                speed,
+            } => {
+                let ceiling = Action::Ceiling {
+                    target: ceiling,
+                    up: true,
+                    speed,
+                    crush: false,
+                    change: None,
+                };
+                let floor = Action::Floor {
+                    target: floor,
+                    up: false,
+                    speed,
+                    crush: false,
+                    change: None,
+                };
+
+                started |= self.run_action(map, line, ceiling, manual);
+                started |= self.run_action(map, line, floor, manual);
+            }
+            Action::Crusher { speed, .. } => {
+                started |= self.set_stasis(map, tag, false);
+
+                for sector in self.idle_sectors(map, line, manual, Plane::Ceiling) {
+                    let floor = map.sector_vec[sector].floor_height;
+                    let ceiling = map.sector_vec[sector].ceil_height;
+
+                    self.start(
+                        sector,
+                        Mover::Ceiling {
+                            height: ceiling as f32,
+                            bottom: (floor + 8) as f32,
+                            top: ceiling as f32,
+                            speed,
+                            direction: -1,
+                            crusher: true,
+                            in_stasis: false,
+                        },
+                    );
+                    started = true;
+                }
+            }
+            Action::StopCrusher => started = self.set_stasis(map, tag, true),
+            Action::Stairs {
+                step,
+                up,
+                speed,
+                ignore_texture,
+            } => {
+                let step = if up { step } else { -step };
+
+                for sector in self.idle_sectors(map, line, manual, Plane::Floor) {
+                    started = true;
+                    self.build_stairs(map, sector, step, speed, ignore_texture);
+                }
+            }
+            Action::Donut => {
+                for sector in self.idle_sectors(map, line, manual, Plane::Floor) {
+                    started |= self.donut(map, sector);
+                }
+            }
+            Action::ChangeTexture(model) => {
+                for sector in map.tagged_sectors(tag) {
+                    let floor = map.sector_vec[sector].floor_height;
+
+                    let model = match model {
+                        ChangeModel::Trigger => trigger_sector,
+                        ChangeModel::Numeric => model_sector(map, sector, floor_of, floor),
+                    };
+
+                    if let Some(model) = model {
+                        map.sector_vec[sector].floor_tex = map.sector_vec[model].floor_tex;
+                        map.sector_vec[sector].special = map.sector_vec[model].special;
+                        self.dirty.insert(sector);
+                        started = true;
+                    }
+                }
+            }
+            Action::Elevator { .. }
+            | Action::Light(_)
+            | Action::Teleport { .. }
+            | Action::Exit { .. }
+            | Action::Effect(_) => {
+                info!("Line {} special is not simulated", line);
+            }
+        }
+
+        started
+    }
+
+    /// put perpetual lifts and crushers of the tagged sectors in or out of stasis
+    fn set_stasis(&mut self, map: &CompleteMap, tag: i16, stasis: bool) -> bool {
+        let mut changed = false;
+
+        let keys = map
+            .tagged_sectors(tag)
+            .into_iter()
+            .flat_map(|sector| [(sector, Plane::Floor), (sector, Plane::Ceiling)]);
+
+        for key in keys {
+            match self.movers.get_mut(&key) {
+                Some(Mover::Plat {
+                    kind: PlatKind::Perpetual,
+                    in_stasis,
+                    ..
+                })
+                | Some(Mover::Ceiling {
+                    crusher: true,
+                    in_stasis,
+                    ..
+                }) if *in_stasis != stasis => {
+                    *in_stasis = stasis;
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+
+        changed
+    }
+
+    /// EV_BuildStairs, every step follows through the front side of the previous one
+    fn build_stairs(
+        &mut self,
+        map: &CompleteMap,
+        first: usize,
+        step: i16,
+        speed: f32,
+        ignore_texture: bool,
+    ) {
+        let texture = map.sector_vec[first].floor_tex;
+        let mut sector = first;
+        let mut height = map.sector_vec[first].floor_height + step;
+
+        loop {
+            self.start(
+                sector,
+                Mover::Floor {
+                    height: map.sector_vec[sector].floor_height as f32,
+                    dest: height as f32,
+                    speed,
+                    texture: None,
+                    special: None,
+                },
+            );
+
+            let mut next = None;
+
+            for &line in &map.sector_vec[sector].linedefs {
+                let (Some(front), Some(back)) = map.line_sectors(line as usize) else {
+                    continue;
+                };
+
+                if front != sector || (!ignore_texture && map.sector_vec[back].floor_tex != texture) {
+                    continue;
+                }
+
+                height += step;
+
+                if self.movers.contains_key(&(back, Plane::Floor)) {
+                    continue;
+                }
+
+                next = Some(back);
+                break;
+            }
+
+            match next {
+                Some(back) => sector = back,
+                None => break,
+            }
+        }
+    }
+
+    /// EV_DoDonut, lowers the pillar and raises the ring around it
+    fn donut(&mut self, map: &CompleteMap, pillar: usize) -> bool {
+        let Some(&first_line) = map.sector_vec[pillar].linedefs.first() else {
+            return false;
+        };
+
+        let ring = match map.line_sectors(first_line as usize) {
+            (Some(front), Some(back)) if front == pillar => back,
+            (Some(front), Some(_)) => front,
+            _ => return false,
+        };
+
+        for &line in &map.sector_vec[ring].linedefs {
+            let (_, Some(outer)) = map.line_sectors(line as usize) else {
+                continue;
+            };
+
+            if outer == pillar {
+                continue;
+            }
+
+            let dest = map.sector_vec[outer].floor_height as f32;
+
+            self.start(
+                ring,
+                Mover::Floor {
+                    height: map.sector_vec[ring].floor_height as f32,
+                    dest,
+                    speed: FLOORSPEED / 2.,
+                    texture: Some(map.sector_vec[outer].floor_tex),
+                    special: Some(0),
+                },
+            );
+
+            self.start(
+                pillar,
+                Mover::Floor {
+                    height: map.sector_vec[pillar].floor_height as f32,
+                    dest,
+                    speed: FLOORSPEED / 2.,
+                    texture: None,
+                    special: None,
+                },
+            );
+
+            return true;
+        }
+
+        false
+    }
+
+    fn tick(&mut self, map: &mut CompleteMap) {
+        let mut finished: Vec<(usize, Plane)> = Vec::new();
+
+        for (&(sector, plane), mover) in self.movers.iter_mut() {
+            let heights = |s: &Sector| (s.floor_height, s.ceil_height);
+            let before = (heights(&map.sector_vec[sector]), map.sector_vec[sector].floor_tex);
+
+            if mover.tick(&mut map.sector_vec[sector]) {
+                finished.push((sector, plane));
+            }
+
+            if map.sector_vec[sector].floor_tex != before.1 {
+                self.dirty.insert(sector);
+            } else if heights(&map.sector_vec[sector]) != before.0 {
+                self.moved.insert(sector);
+            }
+        }
+
+        for key in finished {
+            self.movers.remove(&key);
+        }
+    }
+}
+
+fn run_movers(clock: Res<TicClock>, mut movers: ResMut<SectorMovers>, mut manager: ResMut<MapManager>) {
+    if movers.movers.is_empty() {
+        return;
+    }
+
+    for _ in 0..clock.tics {
+        movers.tick(&mut manager.map);
+    }
+}
+
+/// lines with a side in one of `sectors`
+fn bordering_lines(map: &CompleteMap, sectors: &HashSet<usize>) -> HashSet<usize> {
+    (0..map.linedef_vec.len())
+        .filter(|&line| {
+            let (front, back) = map.line_sectors(line);
+            front.map_or(false, |s| sectors.contains(&s)) || back.map_or(false, |s| sectors.contains(&s))
+        })
+        .collect()
+}
+
+/// respawn the surfaces of retextured sectors, and move the vertices of
+/// the flats and walls of sectors that only changed heights
+fn rebuild_moved_sectors(
+    mut commands: Commands,
+    mut movers: ResMut<SectorMovers>,
+    mut manager: ResMut<MapManager>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut scrollers: Option<ResMut<Scrollers>>,
+    walls: Query<(Entity, &WallSurface, &Handle<Mesh>)>,
+    mut flats: Query<(Entity, &FlatSurface, &mut Transform)>,
+) {
+    if movers.dirty.is_empty() && movers.moved.is_empty() {
+        return;
+    }
+
+    let sectors = std::mem::take(&mut movers.dirty);
+    let moved: HashSet<usize> = std::mem::take(&mut movers.moved)
+        .into_iter()
+        .filter(|sector| !sectors.contains(sector))
+        .collect();
+
+    let lines = bordering_lines(&manager.map, &sectors);
+    let mut moved_lines: HashSet<usize> = &bordering_lines(&manager.map, &moved) - &lines;
+
+    // the shape of a flat never changes, only the height it sits at
+    for (entity, flat, mut transform) in flats.iter_mut() {
+        if sectors.contains(&flat.sector) {
+            commands.entity(entity).despawn();
+        } else if moved.contains(&flat.sector) {
+            let sector = &manager.map.sector_vec[flat.sector];
+            transform.translation.y = if flat.is_ceiling {
+                sector.ceil_height
+            } else {
+                sector.floor_height
+            } as f32;
+        }
+    }
+
+    let mut line_walls: HashMap<usize, Vec<(Entity, WallSurface, Handle<Mesh>)>> = HashMap::new();
+
+    for (entity, wall, mesh) in walls.iter() {
+        if lines.contains(&wall.linedef) {
+            commands.entity(entity).despawn();
+        } else if moved_lines.contains(&wall.linedef) {
+            line_walls
+                .entry(wall.linedef)
+                .or_default()
+                .push((entity, *wall, mesh.clone()));
+        }
+    }
+
+    for (&line, existing) in line_walls.iter() {
+        let rebuilt = manager.linedef_wall_meshes(line);
+
+        // a wall that appears or vanishes at the new heights needs new entities
+        let same_parts = rebuilt.len() == existing.len()
+            && existing
+                .iter()
+                .all(|(_, wall, _)| rebuilt.iter().any(|(surface, _)| surface == wall));
+
+        if !same_parts {
+            continue;
+        }
+
+        for (_, wall, handle) in existing {
+            let Some((_, new_mesh)) = rebuilt.iter().find(|(surface, _)| surface == wall) else {
+                continue;
+            };
+            let Some(mesh) = meshes.get_mut(handle) else {
+                continue;
+            };
+
+            for attribute in [Mesh::ATTRIBUTE_POSITION, Mesh::ATTRIBUTE_UV_0] {
+                if let Some(values) = new_mesh.attribute(attribute.clone()) {
+                    mesh.insert_attribute(attribute, values.clone());
+                }
+            }
+
+            if let Some(scrollers) = scrollers.as_mut() {
+                scrollers.forget(handle);
+            }
+        }
+
+        moved_lines.remove(&line);
+    }
+
+    // the remaining moved lines either had no walls or changed which parts they show
+    for (line, existing) in line_walls.iter() {
+        if moved_lines.contains(line) {
+            for (entity, _, _) in existing {
+                commands.entity(*entity).despawn();
+            }
+        }
+    }
+
+    for &line in lines.iter().chain(moved_lines.iter()) {
+        manager.spawn_linedef_walls(&mut commands, &mut meshes, &mut images, &mut materials, line);
+    }
+
+    for &sector in &sectors {
+        manager.spawn_sector_flats(&mut commands, &mut meshes, &mut images, &mut materials, sector);
+    }
+}
+
+pub struct MoversPlugin;
+
+impl Plugin for MoversPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (run_movers, rebuild_moved_sectors)
+                .chain()
+                .before(run_scrollers)
+                .distributive_run_if(resource_exists::<SectorMovers>())
+                .in_set(OnUpdate(GameState::MapView)),
+        );
+    }
+}
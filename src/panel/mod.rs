@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+/// `key` shows or hides the panel marked with `marker`, the first press spawns
+/// it at `anchor` and returns it so the caller can fill it
+pub fn toggle_panel<T: Component>(
+    commands: &mut Commands,
+    keys: &Input<KeyCode>,
+    key: KeyCode,
+    anchor: UiRect,
+    marker: T,
+    panels: &mut Query<&mut Style, With<T>>,
+) -> Option<Entity> {
+    if !keys.just_pressed(key) {
+        return None;
+    }
+
+    if let Ok(mut style) = panels.get_single_mut() {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+        return None;
+    }
+
+    let panel = commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: anchor,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.)),
+                ..default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.7).into(),
+            ..default()
+        })
+        .insert(marker)
+        .id();
+
+    Some(panel)
+}
+
+/// white monospace text, as the panels show it
+pub fn panel_text_bundle(asset_server: &AssetServer, text: impl Into<String>) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font: asset_server.load("FiraMono-Medium.ttf"),
+            font_size: 16.0,
+            color: Color::WHITE,
+        },
+    )
+}
+
+/// despawns the panels marked with `T`, run when leaving the map view
+pub fn cleanup_panel<T: Component>(mut commands: Commands, panels: Query<Entity, With<T>>) {
+    for entity in panels.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,245 @@
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::utils::HashMap;
+
+use crate::mapmanager::complete_map::CompleteMap;
+use crate::mapmanager::components::{FlatSurface, Side, WallSurface};
+use crate::mapmanager::MapManager;
+use crate::specials::{self, decode, Action, Effect, ScrollMode};
+use crate::state::GameState;
+use crate::tic::TicClock;
+
+/// Boom divides the scrolling linedef vector by 32 to get the speed per tic
+const SCROLL_SHIFT: f32 = 32.;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ScrollTarget {
+    Sidedef(usize),
+    Floor(usize),
+    Ceiling(usize),
+}
+
+struct Scroller {
+    target: ScrollTarget,
+    /// texture units per tic
+    speed: Vec2,
+}
+
+#[derive(Resource)]
+pub struct Scrollers {
+    scrollers: Vec<Scroller>,
+    /// accumulated texture offset of every scrolled target
+    offsets: HashMap<ScrollTarget, Vec2>,
+    /// uvs the meshes had before any scrolling was applied
+    base_uvs: HashMap<Handle<Mesh>, Vec<[f32; 2]>>,
+}
+
+impl Scrollers {
+    pub fn new(map: &CompleteMap) -> Self {
+        let mut scrollers: Vec<Scroller> = Vec::new();
+
+        for (i, linedef) in map.linedef_vec.iter().enumerate() {
+            // lines missing a vertex are reported by the lint
+            let Some((start, end)) = map.line_ends(i) else {
+                continue;
+            };
+            let boom_speed = (end - start) / SCROLL_SHIFT;
+
+            let tagged_sectors = map
+                .sector_vec
+                .iter()
+                .enumerate()
+                .filter(|(_, sector)| sector.tag == linedef.sector_tag)
+                .map(|(s, _)| s);
+
+            let effect = match decode(linedef.special_type).map(|special| special.action) {
+                Some(Action::Effect(effect)) => effect,
+                _ => continue,
+            };
+
+            // only the constant scrollers, the others follow a control sector
+            match effect {
+                Effect::ScrollLeft | Effect::ScrollRight if linedef.front_sidedef >= 0 => scrollers.push(Scroller {
+                    target: ScrollTarget::Sidedef(linedef.front_sidedef as usize),
+                    speed: Vec2::new(if effect == Effect::ScrollLeft { 1. } else { -1. }, 0.),
+                }),
+                Effect::Scroll {
+                    target: specials::ScrollTarget::Ceiling,
+                    mode: ScrollMode::Constant,
+                } => {
+                    for s in tagged_sectors {
+                        scrollers.push(Scroller {
+                            target: ScrollTarget::Ceiling(s),
+                            speed: boom_speed,
+                        });
+                    }
+                }
+                // things are not carried
+                Effect::Scroll {
+                    target: specials::ScrollTarget::Floor | specials::ScrollTarget::FloorAndThings,
+                    mode: ScrollMode::Constant,
+                } => {
+                    for s in tagged_sectors {
+                        scrollers.push(Scroller {
+                            target: ScrollTarget::Floor(s),
+                            speed: boom_speed,
+                        });
+                    }
+                }
+                // scroll tagged walls along the direction of this line
+                Effect::Scroll {
+                    target: specials::ScrollTarget::TaggedWalls,
+                    mode: ScrollMode::Constant,
+                } => {
+                    for (j, tagged) in map.linedef_vec.iter().enumerate() {
+                        if j == i || tagged.sector_tag != linedef.sector_tag || tagged.front_sidedef < 0 {
+                            continue;
+                        }
+
+                        let Some((tagged_start, tagged_end)) = map.line_ends(j) else {
+                            continue;
+                        };
+                        let dir = tagged_end - tagged_start;
+                        let len = dir.length();
+
+                        if len == 0. {
+                            continue;
+                        }
+
+                        scrollers.push(Scroller {
+                            target: ScrollTarget::Sidedef(tagged.front_sidedef as usize),
+                            speed: Vec2::new(
+                                -boom_speed.dot(dir) / len,
+                                -(boom_speed.x * dir.y - boom_speed.y * dir.x) / len,
+                            ),
+                        });
+                    }
+                }
+                // scroll by the sidedef offsets
+                Effect::ScrollByOffsets {
+                    tagged: false,
+                    mode: ScrollMode::Constant,
+                } => {
+                    let Some(side) = map.sidefef_vec.get(linedef.front_sidedef as usize) else {
+                        continue;
+                    };
+
+                    scrollers.push(Scroller {
+                        target: ScrollTarget::Sidedef(linedef.front_sidedef as usize),
+                        speed: Vec2::new(-side.x_off as f32, side.y_off as f32),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Scrollers {
+            scrollers,
+            offsets: HashMap::new(),
+            base_uvs: HashMap::new(),
+        }
+    }
+
+    /// drop the unscrolled uvs kept for a mesh whose vertices were rebuilt
+    pub fn forget(&mut self, mesh: &Handle<Mesh>) {
+        self.base_uvs.remove(mesh);
+    }
+}
+
+pub(crate) fn run_scrollers(
+    clock: Res<TicClock>,
+    mut scrollers: ResMut<Scrollers>,
+    manager: Res<MapManager>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    images: Res<Assets<Image>>,
+    walls: Query<(&WallSurface, &Handle<Mesh>, &Handle<StandardMaterial>)>,
+    flats: Query<(&FlatSurface, &Handle<Mesh>, &Handle<StandardMaterial>)>,
+) {
+    if clock.tics == 0 || scrollers.scrollers.is_empty() {
+        return;
+    }
+
+    let Scrollers {
+        scrollers: list,
+        offsets,
+        base_uvs,
+    } = &mut *scrollers;
+
+    for scroller in list.iter() {
+        *offsets.entry(scroller.target).or_insert(Vec2::ZERO) += scroller.speed * clock.tics as f32;
+    }
+
+    // retextured sectors respawn their surfaces with new meshes
+    base_uvs.retain(|handle, _| meshes.contains(handle));
+
+    let wall_targets = walls.iter().map(|(wall, mesh, material)| {
+        let linedef = &manager.map.linedef_vec[wall.linedef];
+        let sidedef = match wall.side {
+            Side::Front => linedef.front_sidedef,
+            Side::Back => linedef.back_sidedef,
+        };
+        (ScrollTarget::Sidedef(sidedef as usize), mesh, material, 1.)
+    });
+
+    // flat uvs follow the map coordinates, so moving the texture along
+    // the scroll direction means moving the uvs the other way
+    let flat_targets = flats.iter().map(|(flat, mesh, material)| {
+        let target = if flat.is_ceiling {
+            ScrollTarget::Ceiling(flat.sector)
+        } else {
+            ScrollTarget::Floor(flat.sector)
+        };
+        (target, mesh, material, -1.)
+    });
+
+    for (target, mesh_handle, material, sign) in wall_targets.chain(flat_targets) {
+        let Some(offset) = offsets.get(&target) else {
+            continue;
+        };
+
+        let Some(size) = materials
+            .get(material)
+            .and_then(|m| m.base_color_texture.as_ref())
+            .and_then(|image| images.get(image))
+            .map(|image| image.size())
+        else {
+            continue;
+        };
+
+        let Some(mesh) = meshes.get_mut(mesh_handle) else {
+            continue;
+        };
+
+        if !base_uvs.contains_key(mesh_handle) {
+            if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0) {
+                base_uvs.insert(mesh_handle.clone(), uvs.clone());
+            }
+        }
+
+        let Some(base) = base_uvs.get(mesh_handle) else {
+            continue;
+        };
+
+        let shift = Vec2::new(offset.x % size.x / size.x, offset.y % size.y / size.y) * sign;
+
+        let uvs: Vec<[f32; 2]> = base
+            .iter()
+            .map(|uv| [uv[0] + shift.x, uv[1] + shift.y])
+            .collect();
+
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+}
+
+pub struct ScrollingPlugin;
+
+impl Plugin for ScrollingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            run_scrollers
+                .run_if(resource_exists::<Scrollers>())
+                .in_set(OnUpdate(GameState::MapView)),
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use bevy::pbr::{MaterialPipeline, MaterialPipelineKey};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::mesh::MeshVertexBufferLayout;
+use bevy::render::render_resource::{
+    AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+};
+
+use crate::mapmanager::MapManager;
+
+pub const SKY_FLAT: &str = "F_SKY1";
+
+/// draws the sky texture wrapped around the view instead of the surface texture
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "170bda84-cb06-44cc-acd0-35f7696a2dbb"]
+pub struct SkyMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    pub texture: Handle<Image>,
+}
+
+impl Material for SkyMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/sky.wgsl".into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+pub fn is_sky_flat(name: &[u8; 8]) -> bool {
+    std::str::from_utf8(name)
+        .map(|name| name.trim_matches(char::from(0)).eq_ignore_ascii_case(SKY_FLAT))
+        .unwrap_or(false)
+}
+
+/// vanilla sky for an ExMy or MAPxx map
+fn default_sky(map_name: &str) -> String {
+    let name = map_name.to_uppercase();
+
+    if let Some(number) = name.strip_prefix("MAP") {
+        let number: i32 = number.parse().unwrap_or(1);
+
+        return if number < 12 {
+            "SKY1"
+        } else if number < 21 {
+            "SKY2"
+        } else {
+            "SKY3"
+        }
+        .to_string();
+    }
+
+    let bytes = name.as_bytes();
+    if bytes.len() >= 4 && bytes[0] == b'E' && bytes[2] == b'M' && (b'1'..=b'4').contains(&bytes[1]) {
+        return format!("SKY{}", bytes[1] as char);
+    }
+
+    "SKY1".to_string()
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for line in text.lines() {
+        let line = if quoted {
+            line
+        } else {
+            line.split("//").next().unwrap_or("")
+        };
+
+        for c in line.chars() {
+            if c == '"' {
+                quoted = !quoted;
+                if !quoted {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            } else if quoted {
+                current.push(c);
+            } else if c.is_whitespace() || "{}=,".contains(c) {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                // braces are kept so the parser knows where blocks end
+                if c == '{' || c == '}' {
+                    tokens.push(c.to_string());
+                }
+            } else {
+                current.push(c);
+            }
+        }
+
+        if !quoted && !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+    }
+
+    tokens
+}
+
+/// keywords besides `map` opening a top level block in the brace-less
+/// Hexen MAPINFO, where a map block only ends when the next block starts
+const MAPINFO_BLOCKS: [&str; 8] = [
+    "defaultmap",
+    "adddefaultmap",
+    "gamedefaults",
+    "clusterdef",
+    "episode",
+    "clearepisodes",
+    "skill",
+    "clearskills",
+];
+
+/// `sky1` (MAPINFO/ZMAPINFO) or `skytexture` (UMAPINFO) of a map block
+fn mapinfo_sky(text: &str, map_name: &str) -> Option<String> {
+    let tokens = tokenize(text);
+    let mut in_map = false;
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i].to_lowercase();
+
+        match token.as_str() {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                if depth <= 0 {
+                    depth = 0;
+                    in_map = false;
+                }
+            }
+            _ => {}
+        }
+
+        if depth == 0 && token == "map" && i + 1 < tokens.len() {
+            in_map = tokens[i + 1].eq_ignore_ascii_case(map_name);
+            i += 2;
+            continue;
+        }
+
+        if depth == 0 && MAPINFO_BLOCKS.contains(&token.as_str()) {
+            in_map = false;
+        }
+
+        if in_map && (token == "sky1" || token == "skytexture") {
+            return tokens.get(i + 1).map(|sky| sky.to_uppercase());
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+pub fn sky_texture_name(manager: &MapManager) -> String {
+    for wad in &manager.res_wads {
+        for lump_name in ["UMAPINFO", "ZMAPINFO", "MAPINFO"] {
+            let Some(lump) = wad.lump(lump_name) else {
+                continue;
+            };
+
+            let text = String::from_utf8_lossy(&lump.data().buffer).to_string();
+
+            if let Some(sky) = mapinfo_sky(&text, &manager.map_name) {
+                return sky;
+            }
+        }
+    }
+
+    default_sky(&manager.map_name)
+}
+
+pub fn create_sky_material(
+    manager: &mut MapManager,
+    images: &mut Assets<Image>,
+    sky_materials: &mut Assets<SkyMaterial>,
+) -> Handle<SkyMaterial> {
+    let sky_name = sky_texture_name(manager);
+
+    info!("Sky texture {}", sky_name);
+
+    // texture names are stored padded to 8 bytes
+    match manager.get_image(images, format!("{:\0<8}", sky_name)) {
+        Ok(texture) => sky_materials.add(SkyMaterial { texture }),
+        Err(err) => {
+            error!(err);
+            Handle::default()
+        }
+    }
+}
+
+pub struct SkyPlugin;
+
+impl Plugin for SkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MaterialPlugin::<SkyMaterial>::default());
+    }
+}
@@ -0,0 +1,201 @@
+use super::*;
+
+/// Boom (and MBF) parameterless specials from boomref.txt, plus the MBF21 additions
+pub fn boom_special(special: i16) -> Option<LineSpecial> {
+    use self::CeilingTarget as C;
+    use self::FloorTarget as F;
+    use self::Trigger::*;
+
+    let turbo = FLOORSPEED * 4.;
+
+    let scroll = |target, mode| Action::Effect(Effect::Scroll { target, mode });
+    let offsets = |mode| Action::Effect(Effect::ScrollByOffsets { tagged: true, mode });
+    let elevator = |target| Action::Elevator {
+        target,
+        speed: ELEVATORSPEED,
+    };
+    let silent_teleport = |monsters_only, line, reversed| Action::Teleport {
+        monsters_only,
+        silent: true,
+        line,
+        reversed,
+    };
+
+    let mut source = Source::Boom;
+
+    let (trigger, repeatable, action) = match special {
+        78 => (Switch, true, Action::ChangeTexture(ChangeModel::Numeric)),
+        85 => (Passive, false, Action::Effect(Effect::ScrollRight)),
+        142 => (Walk, false, floor(F::By(512), FLOORSPEED)),
+        143 => (Walk, false, plat(PlatKind::RaiseAndChange(24), PLATSPEED / 2.)),
+        144 => (Walk, false, plat(PlatKind::RaiseAndChange(32), PLATSPEED / 2.)),
+        145 => (Walk, false, ceiling(C::Floor, false)),
+        146 => (Walk, false, Action::Donut),
+        147 => (Walk, true, floor(F::By(512), FLOORSPEED)),
+        148 => (Walk, true, plat(PlatKind::RaiseAndChange(24), PLATSPEED / 2.)),
+        149 => (Walk, true, plat(PlatKind::RaiseAndChange(32), PLATSPEED / 2.)),
+        150 => (Walk, true, Action::Crusher { speed: CEILSPEED, silent: true }),
+        151 => (Walk, true, ceiling(C::HighestCeiling, false)),
+        152 => (Walk, true, ceiling(C::Floor, false)),
+        153 => (Walk, false, Action::ChangeTexture(ChangeModel::Trigger)),
+        154 => (Walk, true, Action::ChangeTexture(ChangeModel::Trigger)),
+        155 => (Walk, true, Action::Donut),
+        156 => (Walk, true, Action::Light(LightTarget::Blink)),
+        157 => (Walk, true, Action::Light(LightTarget::DarkestNeighbour)),
+        158 => (Switch, false, floor(F::ShortestLowerTexture, FLOORSPEED)),
+        159 => (Switch, false, floor_change(F::LowestFloor, FLOORSPEED, ChangeModel::Numeric)),
+        160 => (Switch, false, floor_change(F::By(24), FLOORSPEED, ChangeModel::Trigger)),
+        161 => (Switch, false, floor(F::By(24), FLOORSPEED)),
+        162 => (Switch, false, plat(PlatKind::Perpetual, PLATSPEED)),
+        163 => (Switch, false, Action::StopPlat),
+        164 => (Switch, false, Action::Crusher { speed: CEILSPEED * 2., silent: false }),
+        165 => (Switch, false, Action::Crusher { speed: CEILSPEED, silent: true }),
+        166 => (Switch, false, ceiling(C::HighestCeiling, false)),
+        167 => (Switch, false, ceiling(C::FloorPlus8, true)),
+        168 => (Switch, false, Action::StopCrusher),
+        169 => (Switch, false, Action::Light(LightTarget::BrightestNeighbour)),
+        170 => (Switch, false, Action::Light(LightTarget::Level(35))),
+        171 => (Switch, false, Action::Light(LightTarget::Level(255))),
+        172 => (Switch, false, Action::Light(LightTarget::Blink)),
+        173 => (Switch, false, Action::Light(LightTarget::DarkestNeighbour)),
+        174 => (Switch, false, teleport(false)),
+        175 => (Switch, false, door(DoorKind::CloseWaitOpen, VDOORSPEED)),
+        176 => (Switch, true, floor(F::ShortestLowerTexture, FLOORSPEED)),
+        177 => (Switch, true, floor_change(F::LowestFloor, FLOORSPEED, ChangeModel::Numeric)),
+        178 => (Switch, true, floor(F::By(512), FLOORSPEED)),
+        179 => (Switch, true, floor_change(F::By(24), FLOORSPEED, ChangeModel::Trigger)),
+        180 => (Switch, true, floor(F::By(24), FLOORSPEED)),
+        181 => (Switch, true, plat(PlatKind::Perpetual, PLATSPEED)),
+        182 => (Switch, true, Action::StopPlat),
+        183 => (Switch, true, Action::Crusher { speed: CEILSPEED * 2., silent: false }),
+        184 => (Switch, true, Action::Crusher { speed: CEILSPEED, silent: false }),
+        185 => (Switch, true, Action::Crusher { speed: CEILSPEED, silent: true }),
+        186 => (Switch, true, ceiling(C::HighestCeiling, false)),
+        187 => (Switch, true, ceiling(C::FloorPlus8, true)),
+        188 => (Switch, true, Action::StopCrusher),
+        189 => (Switch, false, Action::ChangeTexture(ChangeModel::Trigger)),
+        190 => (Switch, true, Action::ChangeTexture(ChangeModel::Trigger)),
+        191 => (Switch, true, Action::Donut),
+        192 => (Switch, true, Action::Light(LightTarget::BrightestNeighbour)),
+        193 => (Switch, true, Action::Light(LightTarget::Blink)),
+        194 => (Switch, true, Action::Light(LightTarget::DarkestNeighbour)),
+        195 => (Switch, true, teleport(false)),
+        196 => (Switch, true, door(DoorKind::CloseWaitOpen, VDOORSPEED)),
+        197 => (Gun, false, Action::Exit { secret: false }),
+        198 => (Gun, false, Action::Exit { secret: true }),
+        199 => (Walk, false, ceiling(C::LowestCeiling, false)),
+        200 => (Walk, false, ceiling(C::HighestFloor, false)),
+        201 => (Walk, true, ceiling(C::LowestCeiling, false)),
+        202 => (Walk, true, ceiling(C::HighestFloor, false)),
+        203 => (Switch, false, ceiling(C::LowestCeiling, false)),
+        204 => (Switch, false, ceiling(C::HighestFloor, false)),
+        205 => (Switch, true, ceiling(C::LowestCeiling, false)),
+        206 => (Switch, true, ceiling(C::HighestFloor, false)),
+        207 => (Walk, false, silent_teleport(false, false, false)),
+        208 => (Walk, true, silent_teleport(false, false, false)),
+        209 => (Switch, false, silent_teleport(false, false, false)),
+        210 => (Switch, true, silent_teleport(false, false, false)),
+        211 => (Switch, true, plat(PlatKind::Toggle, 0.)),
+        212 => (Walk, true, plat(PlatKind::Toggle, 0.)),
+        213 => (Passive, false, Action::Effect(Effect::TransferFloorLight)),
+        214 => (Passive, false, scroll(ScrollTarget::Ceiling, ScrollMode::Accelerative)),
+        215 => (Passive, false, scroll(ScrollTarget::Floor, ScrollMode::Accelerative)),
+        216 => (Passive, false, scroll(ScrollTarget::Things, ScrollMode::Accelerative)),
+        217 => (Passive, false, scroll(ScrollTarget::FloorAndThings, ScrollMode::Accelerative)),
+        218 => (Passive, false, scroll(ScrollTarget::TaggedWalls, ScrollMode::Accelerative)),
+        219 => (Walk, false, floor(F::NextLower, FLOORSPEED)),
+        220 => (Walk, true, floor(F::NextLower, FLOORSPEED)),
+        221 => (Switch, false, floor(F::NextLower, FLOORSPEED)),
+        222 => (Switch, true, floor(F::NextLower, FLOORSPEED)),
+        223 => (Passive, false, Action::Effect(Effect::Friction)),
+        224 => (Passive, false, Action::Effect(Effect::Wind)),
+        225 => (Passive, false, Action::Effect(Effect::Current)),
+        226 => (Passive, false, Action::Effect(Effect::PointPusher)),
+        227 => (Walk, false, elevator(ElevatorTarget::NextHigher)),
+        228 => (Walk, true, elevator(ElevatorTarget::NextHigher)),
+        229 => (Switch, false, elevator(ElevatorTarget::NextHigher)),
+        230 => (Switch, true, elevator(ElevatorTarget::NextHigher)),
+        231 => (Walk, false, elevator(ElevatorTarget::NextLower)),
+        232 => (Walk, true, elevator(ElevatorTarget::NextLower)),
+        233 => (Switch, false, elevator(ElevatorTarget::NextLower)),
+        234 => (Switch, true, elevator(ElevatorTarget::NextLower)),
+        235 => (Walk, false, elevator(ElevatorTarget::Current)),
+        236 => (Walk, true, elevator(ElevatorTarget::Current)),
+        237 => (Switch, false, elevator(ElevatorTarget::Current)),
+        238 => (Switch, true, elevator(ElevatorTarget::Current)),
+        239 => (Walk, false, Action::ChangeTexture(ChangeModel::Numeric)),
+        240 => (Walk, true, Action::ChangeTexture(ChangeModel::Numeric)),
+        241 => (Switch, false, Action::ChangeTexture(ChangeModel::Numeric)),
+        242 => (Passive, false, Action::Effect(Effect::FakeFloors)),
+        243 => (Walk, false, silent_teleport(false, true, false)),
+        244 => (Walk, true, silent_teleport(false, true, false)),
+        245 => (Passive, false, scroll(ScrollTarget::Ceiling, ScrollMode::Displacement)),
+        246 => (Passive, false, scroll(ScrollTarget::Floor, ScrollMode::Displacement)),
+        247 => (Passive, false, scroll(ScrollTarget::Things, ScrollMode::Displacement)),
+        248 => (Passive, false, scroll(ScrollTarget::FloorAndThings, ScrollMode::Displacement)),
+        249 => (Passive, false, scroll(ScrollTarget::TaggedWalls, ScrollMode::Displacement)),
+        250 => (Passive, false, scroll(ScrollTarget::Ceiling, ScrollMode::Constant)),
+        251 => (Passive, false, scroll(ScrollTarget::Floor, ScrollMode::Constant)),
+        252 => (Passive, false, scroll(ScrollTarget::Things, ScrollMode::Constant)),
+        253 => (Passive, false, scroll(ScrollTarget::FloorAndThings, ScrollMode::Constant)),
+        254 => (Passive, false, scroll(ScrollTarget::TaggedWalls, ScrollMode::Constant)),
+        255 => (
+            Passive,
+            false,
+            Action::Effect(Effect::ScrollByOffsets {
+                tagged: false,
+                mode: ScrollMode::Constant,
+            }),
+        ),
+        256 => (Walk, true, stairs(8, FLOORSPEED / 4.)),
+        257 => (Walk, true, stairs(16, turbo)),
+        258 => (Switch, true, stairs(8, FLOORSPEED / 4.)),
+        259 => (Switch, true, stairs(16, turbo)),
+        260 => (Passive, false, Action::Effect(Effect::Translucent)),
+        261 => (Passive, false, Action::Effect(Effect::TransferCeilingLight)),
+        262 => (Walk, false, silent_teleport(false, true, true)),
+        263 => (Walk, true, silent_teleport(false, true, true)),
+        264 => (Walk, false, silent_teleport(true, true, true)),
+        265 => (Walk, true, silent_teleport(true, true, true)),
+        266 => (Walk, false, silent_teleport(true, true, false)),
+        267 => (Walk, true, silent_teleport(true, true, false)),
+        268 => (Walk, false, silent_teleport(true, false, false)),
+        269 => (Walk, true, silent_teleport(true, false, false)),
+        271 | 272 => {
+            source = Source::Mbf;
+            (
+                Passive,
+                false,
+                Action::Effect(Effect::TransferSky {
+                    flipped: special == 272,
+                }),
+            )
+        }
+        1024 => {
+            source = Source::Mbf21;
+            (Passive, false, offsets(ScrollMode::Constant))
+        }
+        1025 => {
+            source = Source::Mbf21;
+            (Passive, false, offsets(ScrollMode::Displacement))
+        }
+        1026 => {
+            source = Source::Mbf21;
+            (Passive, false, offsets(ScrollMode::Accelerative))
+        }
+        _ => return None,
+    };
+
+    let monsters = match action {
+        Action::Teleport { monsters_only, .. } => monsters_only || trigger == Walk,
+        _ => false,
+    };
+
+    Some(LineSpecial {
+        source,
+        trigger,
+        repeatable,
+        monsters,
+        action,
+    })
+}
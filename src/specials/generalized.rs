@@ -0,0 +1,247 @@
+use super::*;
+
+/// first number of every generalized class (p_spec.h)
+const GEN_CRUSHER_BASE: i32 = 0x2f80;
+const GEN_STAIRS_BASE: i32 = 0x3000;
+const GEN_LIFT_BASE: i32 = 0x3400;
+const GEN_LOCKED_BASE: i32 = 0x3800;
+const GEN_DOOR_BASE: i32 = 0x3c00;
+const GEN_CEILING_BASE: i32 = 0x4000;
+const GEN_FLOOR_BASE: i32 = 0x6000;
+
+/// reads `width` bits starting at `shift`
+fn field(special: i32, shift: u32, width: u32) -> i32 {
+    (special >> shift) & ((1 << width) - 1)
+}
+
+fn flag(special: i32, bit: u32) -> bool {
+    special & (1 << bit) != 0
+}
+
+/// bits 0-2: W1 WR S1 SR G1 GR D1 DR
+fn trigger(special: i32) -> (Trigger, bool) {
+    let kind = field(special, 0, 3);
+
+    let trigger = match kind >> 1 {
+        0 => Trigger::Walk,
+        1 => Trigger::Switch,
+        2 => Trigger::Gun,
+        _ => Trigger::Manual,
+    };
+
+    (trigger, kind & 1 != 0)
+}
+
+/// bits 3-4: slow, normal, fast, turbo as a multiple of the slow speed
+fn speed(special: i32, slow: f32) -> f32 {
+    slow * (1 << field(special, 3, 2)) as f32
+}
+
+/// bits 10-11 of floors and ceilings, with the model in bit 5
+fn change(special: i32) -> Option<Change> {
+    let model = if flag(special, 5) {
+        ChangeModel::Numeric
+    } else {
+        ChangeModel::Trigger
+    };
+
+    let sector_type = match field(special, 10, 2) {
+        0 => return None,
+        1 => TypeChange::Zero,
+        2 => TypeChange::Keep,
+        _ => TypeChange::Copy,
+    };
+
+    Some(Change { model, sector_type })
+}
+
+/// Boom generalized linedef types, 0x2f80 to 0x7fff
+pub fn generalized_special(special: i16) -> Option<LineSpecial> {
+    let special = special as i32;
+
+    if special < GEN_CRUSHER_BASE {
+        return None;
+    }
+
+    let (trigger, repeatable) = trigger(special);
+
+    let (monsters, action) = if special >= GEN_FLOOR_BASE {
+        let up = flag(special, 6);
+        let change = change(special);
+
+        let target = match field(special, 7, 3) {
+            0 => FloorTarget::HighestFloor,
+            1 => FloorTarget::LowestFloor,
+            2 if up => FloorTarget::NextHigher,
+            2 => FloorTarget::NextLower,
+            3 => FloorTarget::LowestCeiling,
+            4 => FloorTarget::Ceiling,
+            5 => FloorTarget::ShortestLowerTexture,
+            6 => FloorTarget::By(if up { 24 } else { -24 }),
+            _ => FloorTarget::By(if up { 32 } else { -32 }),
+        };
+
+        (
+            change.is_none() && flag(special, 5),
+            Action::Floor {
+                target,
+                up,
+                speed: speed(special, FLOORSPEED),
+                crush: flag(special, 12),
+                change,
+            },
+        )
+    } else if special >= GEN_CEILING_BASE {
+        let up = flag(special, 6);
+        let change = change(special);
+
+        let target = match field(special, 7, 3) {
+            0 => CeilingTarget::HighestCeiling,
+            1 => CeilingTarget::LowestCeiling,
+            2 if up => CeilingTarget::NextHigher,
+            2 => CeilingTarget::NextLower,
+            3 => CeilingTarget::HighestFloor,
+            4 => CeilingTarget::Floor,
+            5 => CeilingTarget::ShortestUpperTexture,
+            6 => CeilingTarget::By(if up { 24 } else { -24 }),
+            _ => CeilingTarget::By(if up { 32 } else { -32 }),
+        };
+
+        (
+            change.is_none() && flag(special, 5),
+            Action::Ceiling {
+                target,
+                up,
+                speed: speed(special, CEILSPEED),
+                crush: flag(special, 12),
+                change,
+            },
+        )
+    } else if special >= GEN_DOOR_BASE {
+        let kind = match field(special, 5, 2) {
+            0 => DoorKind::OpenWaitClose,
+            1 => DoorKind::Open,
+            2 => DoorKind::CloseWaitOpen,
+            _ => DoorKind::Close,
+        };
+
+        let wait = match field(special, 8, 2) {
+            0 => 35,
+            1 => 150,
+            2 => 300,
+            _ => 1050,
+        };
+
+        (
+            flag(special, 7),
+            Action::Door {
+                kind,
+                speed: speed(special, VDOORSPEED),
+                wait: if matches!(kind, DoorKind::Open | DoorKind::Close) {
+                    0
+                } else {
+                    wait
+                },
+                key: None,
+            },
+        )
+    } else if special >= GEN_LOCKED_BASE {
+        let kind = if flag(special, 5) {
+            DoorKind::Open
+        } else {
+            DoorKind::OpenWaitClose
+        };
+
+        // bit 9 makes cards and skulls of a colour interchangeable
+        let either = flag(special, 9);
+
+        let key = match field(special, 6, 3) {
+            0 => Key::Any,
+            1 if either => Key::Red,
+            1 => Key::RedCard,
+            2 if either => Key::Blue,
+            2 => Key::BlueCard,
+            3 if either => Key::Yellow,
+            3 => Key::YellowCard,
+            4 if either => Key::Red,
+            4 => Key::RedSkull,
+            5 if either => Key::Blue,
+            5 => Key::BlueSkull,
+            6 if either => Key::Yellow,
+            6 => Key::YellowSkull,
+            _ if either => Key::AllThree,
+            _ => Key::AllSix,
+        };
+
+        (
+            false,
+            Action::Door {
+                kind,
+                speed: speed(special, VDOORSPEED),
+                wait: if kind == DoorKind::Open { 0 } else { VDOORWAIT },
+                key: Some(key),
+            },
+        )
+    } else if special >= GEN_LIFT_BASE {
+        let kind = match field(special, 8, 2) {
+            0 => PlatKind::DownWaitUp,
+            1 => PlatKind::DownToNextLowerWaitUp,
+            2 => PlatKind::DownToLowestCeilingWaitUp,
+            _ => PlatKind::Perpetual,
+        };
+
+        let wait = match field(special, 6, 2) {
+            0 => 35,
+            1 => 105,
+            2 => 175,
+            _ => 350,
+        };
+
+        (
+            flag(special, 5),
+            Action::Plat {
+                kind,
+                speed: speed(special, PLATSPEED * 2.),
+                wait,
+            },
+        )
+    } else if special >= GEN_STAIRS_BASE {
+        let step = match field(special, 6, 2) {
+            0 => 4,
+            1 => 8,
+            2 => 16,
+            _ => 24,
+        };
+
+        (
+            flag(special, 5),
+            Action::Stairs {
+                step,
+                up: flag(special, 8),
+                speed: match field(special, 3, 2) {
+                    0 => FLOORSPEED / 4.,
+                    1 => FLOORSPEED / 2.,
+                    2 => FLOORSPEED * 2.,
+                    _ => FLOORSPEED * 4.,
+                },
+                ignore_texture: flag(special, 9),
+            },
+        )
+    } else {
+        (
+            flag(special, 5),
+            Action::Crusher {
+                speed: speed(special, CEILSPEED),
+                silent: flag(special, 6),
+            },
+        )
+    };
+
+    Some(LineSpecial {
+        source: Source::Generalized,
+        trigger,
+        repeatable,
+        monsters,
+        action,
+    })
+}
@@ -0,0 +1,885 @@
+mod boom;
+mod generalized;
+
+use std::fmt::{Display, Formatter, Result};
+
+/// vanilla mover speeds in map units per tic (p_spec.h)
+pub const VDOORSPEED: f32 = 2.;
+pub const PLATSPEED: f32 = 1.;
+pub const FLOORSPEED: f32 = 1.;
+pub const CEILSPEED: f32 = 1.;
+/// Boom elevators move both planes at this speed
+pub const ELEVATORSPEED: f32 = 4.;
+
+/// tics a door stays open before closing
+pub const VDOORWAIT: u32 = 150;
+/// tics a lift waits at either end
+pub const PLATWAIT: u32 = 3 * 35;
+/// tics a close-wait-open door stays shut
+pub const DOORCLOSEWAIT: u32 = 30 * 35;
+
+/// How a linedef special is activated
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Trigger {
+    /// crossing the line
+    Walk,
+    /// using the line, acts on the tagged sectors
+    Switch,
+    /// using the line, acts on the sector behind it
+    Manual,
+    /// shooting the line
+    Gun,
+    /// always in effect from the start of the map
+    Passive,
+}
+
+/// Which set of specials a number belongs to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Source {
+    Vanilla,
+    Boom,
+    /// Boom bit field specials, 0x2f80 and above
+    Generalized,
+    Mbf,
+    Mbf21,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key {
+    Any,
+    /// card or skull of that colour
+    Blue,
+    Yellow,
+    Red,
+    BlueCard,
+    YellowCard,
+    RedCard,
+    BlueSkull,
+    YellowSkull,
+    RedSkull,
+    /// one key of each colour
+    AllThree,
+    AllSix,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DoorKind {
+    OpenWaitClose,
+    Open,
+    Close,
+    CloseWaitOpen,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlatKind {
+    /// down to the lowest neighbouring floor, wait, back up
+    DownWaitUp,
+    DownToNextLowerWaitUp,
+    DownToLowestCeilingWaitUp,
+    Perpetual,
+    /// instantly swap the floor between its height and the ceiling
+    Toggle,
+    /// raise to the next higher floor, taking the front sector floor texture
+    RaiseToNearestAndChange,
+    /// raise by an amount, taking the front sector floor texture
+    RaiseAndChange(i16),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FloorTarget {
+    LowestCeiling,
+    /// 8 below the lowest neighbouring ceiling
+    LowestCeilingMinus8,
+    NextHigher,
+    NextLower,
+    LowestFloor,
+    HighestFloor,
+    /// 8 above the highest neighbouring floor
+    HighestFloorPlus8,
+    /// the sector's own ceiling
+    Ceiling,
+    /// relative to the current floor
+    By(i16),
+    ShortestLowerTexture,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CeilingTarget {
+    /// the sector's own floor
+    Floor,
+    /// 8 above the floor
+    FloorPlus8,
+    HighestCeiling,
+    LowestCeiling,
+    NextHigher,
+    NextLower,
+    HighestFloor,
+    /// relative to the current ceiling
+    By(i16),
+    ShortestUpperTexture,
+}
+
+/// Where a mover takes its new texture from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ChangeModel {
+    /// front sector of the activating line
+    Trigger,
+    /// neighbouring sector whose plane is at the destination height
+    Numeric,
+}
+
+/// What happens to the sector type when the texture changes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TypeChange {
+    Keep,
+    Zero,
+    Copy,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Change {
+    pub model: ChangeModel,
+    pub sector_type: TypeChange,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ElevatorTarget {
+    NextHigher,
+    NextLower,
+    /// floor of the sector in front of the activating line
+    Current,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightTarget {
+    Level(i16),
+    BrightestNeighbour,
+    DarkestNeighbour,
+    Blink,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScrollTarget {
+    Ceiling,
+    Floor,
+    Things,
+    FloorAndThings,
+    TaggedWalls,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScrollMode {
+    Constant,
+    /// follows the height changes of the control sector
+    Displacement,
+    /// speeds up with the height changes of the control sector
+    Accelerative,
+}
+
+/// Passive line effects
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Effect {
+    ScrollLeft,
+    ScrollRight,
+    Scroll {
+        target: ScrollTarget,
+        mode: ScrollMode,
+    },
+    /// scroll by the sidedef offsets, of this line or of the tagged lines
+    ScrollByOffsets {
+        tagged: bool,
+        mode: ScrollMode,
+    },
+    TransferFloorLight,
+    TransferCeilingLight,
+    Friction,
+    Wind,
+    Current,
+    PointPusher,
+    FakeFloors,
+    Translucent,
+    TransferSky {
+        flipped: bool,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    Door {
+        kind: DoorKind,
+        speed: f32,
+        /// tics spent open (or closed) before moving back
+        wait: u32,
+        key: Option<Key>,
+    },
+    Plat {
+        kind: PlatKind,
+        speed: f32,
+        wait: u32,
+    },
+    StopPlat,
+    Floor {
+        target: FloorTarget,
+        up: bool,
+        speed: f32,
+        crush: bool,
+        change: Option<Change>,
+    },
+    Ceiling {
+        target: CeilingTarget,
+        up: bool,
+        speed: f32,
+        crush: bool,
+        change: Option<Change>,
+    },
+    /// both planes of the tagged sectors at once, EV_DoCeiling then EV_DoFloor
+    RaiseCeilingLowerFloor {
+        ceiling: CeilingTarget,
+        floor: FloorTarget,
+        speed: f32,
+    },
+    Crusher {
+        speed: f32,
+        silent: bool,
+    },
+    StopCrusher,
+    Stairs {
+        step: i16,
+        up: bool,
+        speed: f32,
+        ignore_texture: bool,
+    },
+    Donut,
+    Elevator {
+        target: ElevatorTarget,
+        speed: f32,
+    },
+    /// copy a floor texture and sector type onto the tagged sectors
+    ChangeTexture(ChangeModel),
+    Light(LightTarget),
+    Teleport {
+        monsters_only: bool,
+        silent: bool,
+        /// to the tagged line instead of a teleport destination thing
+        line: bool,
+        reversed: bool,
+    },
+    Exit {
+        secret: bool,
+    },
+    Effect(Effect),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LineSpecial {
+    pub source: Source,
+    pub trigger: Trigger,
+    pub repeatable: bool,
+    /// monsters can activate it too
+    pub monsters: bool,
+    pub action: Action,
+}
+
+/// Any linedef special number, vanilla, Boom, generalized or MBF21
+pub fn decode(special: i16) -> Option<LineSpecial> {
+    vanilla_special(special)
+        .or_else(|| boom::boom_special(special))
+        .or_else(|| generalized::generalized_special(special))
+}
+
+/// Human-readable name of a linedef special, like "SR Door Open Wait Close"
+pub fn describe(special: i16) -> String {
+    match special {
+        0 => "None".to_string(),
+        _ => match decode(special) {
+            Some(line_special) => line_special.to_string(),
+            None => format!("Unknown special {}", special),
+        },
+    }
+}
+
+fn default_door_wait(kind: DoorKind) -> u32 {
+    match kind {
+        DoorKind::OpenWaitClose => VDOORWAIT,
+        DoorKind::CloseWaitOpen => DOORCLOSEWAIT,
+        _ => 0,
+    }
+}
+
+fn door(kind: DoorKind, speed: f32) -> Action {
+    Action::Door {
+        kind,
+        speed,
+        wait: default_door_wait(kind),
+        key: None,
+    }
+}
+
+fn locked(kind: DoorKind, speed: f32, key: Key) -> Action {
+    Action::Door {
+        kind,
+        speed,
+        wait: default_door_wait(kind),
+        key: Some(key),
+    }
+}
+
+fn plat(kind: PlatKind, speed: f32) -> Action {
+    Action::Plat {
+        kind,
+        speed,
+        wait: PLATWAIT,
+    }
+}
+
+/// whether a fixed floor target lies above the floor
+fn floor_goes_up(target: FloorTarget) -> bool {
+    match target {
+        FloorTarget::LowestFloor
+        | FloorTarget::HighestFloor
+        | FloorTarget::HighestFloorPlus8
+        | FloorTarget::NextLower => false,
+        FloorTarget::By(amount) => amount > 0,
+        _ => true,
+    }
+}
+
+fn floor(target: FloorTarget, speed: f32) -> Action {
+    Action::Floor {
+        target,
+        up: floor_goes_up(target),
+        speed,
+        crush: false,
+        change: None,
+    }
+}
+
+fn floor_change(target: FloorTarget, speed: f32, model: ChangeModel) -> Action {
+    Action::Floor {
+        target,
+        up: floor_goes_up(target),
+        speed,
+        crush: false,
+        change: Some(Change {
+            model,
+            sector_type: TypeChange::Copy,
+        }),
+    }
+}
+
+fn crush_floor(target: FloorTarget) -> Action {
+    Action::Floor {
+        target,
+        up: true,
+        speed: FLOORSPEED,
+        crush: true,
+        change: None,
+    }
+}
+
+fn ceiling(target: CeilingTarget, crush: bool) -> Action {
+    Action::Ceiling {
+        target,
+        up: target == CeilingTarget::HighestCeiling,
+        speed: CEILSPEED,
+        crush,
+        change: None,
+    }
+}
+
+fn stairs(step: i16, speed: f32) -> Action {
+    Action::Stairs {
+        step,
+        up: true,
+        speed,
+        ignore_texture: false,
+    }
+}
+
+fn teleport(monsters_only: bool) -> Action {
+    Action::Teleport {
+        monsters_only,
+        silent: false,
+        line: false,
+        reversed: false,
+    }
+}
+
+/// Vanilla Doom linedef special (p_spec.c, p_switch.c)
+pub fn vanilla_special(special: i16) -> Option<LineSpecial> {
+    use self::CeilingTarget as C;
+    use self::DoorKind::*;
+    use self::FloorTarget as F;
+    use self::Trigger::*;
+
+    let blaze = VDOORSPEED * 4.;
+    let turbo = FLOORSPEED * 4.;
+
+    let (trigger, repeatable, action) = match special {
+        1 => (Manual, true, door(OpenWaitClose, VDOORSPEED)),
+        2 => (Walk, false, door(Open, VDOORSPEED)),
+        3 => (Walk, false, door(Close, VDOORSPEED)),
+        4 => (Walk, false, door(OpenWaitClose, VDOORSPEED)),
+        5 => (Walk, false, floor(F::LowestCeiling, FLOORSPEED)),
+        6 => (Walk, false, Action::Crusher { speed: CEILSPEED * 2., silent: false }),
+        7 => (Switch, false, stairs(8, FLOORSPEED / 4.)),
+        8 => (Walk, false, stairs(8, FLOORSPEED / 4.)),
+        9 => (Switch, false, Action::Donut),
+        10 => (Walk, false, plat(PlatKind::DownWaitUp, PLATSPEED * 4.)),
+        11 => (Switch, false, Action::Exit { secret: false }),
+        12 => (Walk, false, Action::Light(LightTarget::BrightestNeighbour)),
+        13 => (Walk, false, Action::Light(LightTarget::Level(255))),
+        14 => (Switch, false, plat(PlatKind::RaiseAndChange(32), PLATSPEED / 2.)),
+        15 => (Switch, false, plat(PlatKind::RaiseAndChange(24), PLATSPEED / 2.)),
+        16 => (Walk, false, door(CloseWaitOpen, VDOORSPEED)),
+        17 => (Walk, false, Action::Light(LightTarget::Blink)),
+        18 => (Switch, false, floor(F::NextHigher, FLOORSPEED)),
+        19 => (Walk, false, floor(F::HighestFloor, FLOORSPEED)),
+        20 => (Switch, false, plat(PlatKind::RaiseToNearestAndChange, PLATSPEED / 2.)),
+        21 => (Switch, false, plat(PlatKind::DownWaitUp, PLATSPEED * 4.)),
+        22 => (Walk, false, plat(PlatKind::RaiseToNearestAndChange, PLATSPEED / 2.)),
+        23 => (Switch, false, floor(F::LowestFloor, FLOORSPEED)),
+        24 => (Gun, false, floor(F::LowestCeiling, FLOORSPEED)),
+        25 => (Walk, false, Action::Crusher { speed: CEILSPEED, silent: false }),
+        26 => (Manual, true, locked(OpenWaitClose, VDOORSPEED, Key::Blue)),
+        27 => (Manual, true, locked(OpenWaitClose, VDOORSPEED, Key::Yellow)),
+        28 => (Manual, true, locked(OpenWaitClose, VDOORSPEED, Key::Red)),
+        29 => (Switch, false, door(OpenWaitClose, VDOORSPEED)),
+        30 => (Walk, false, floor(F::ShortestLowerTexture, FLOORSPEED)),
+        31 => (Manual, false, door(Open, VDOORSPEED)),
+        32 => (Manual, false, locked(Open, VDOORSPEED, Key::Blue)),
+        33 => (Manual, false, locked(Open, VDOORSPEED, Key::Red)),
+        34 => (Manual, false, locked(Open, VDOORSPEED, Key::Yellow)),
+        35 => (Walk, false, Action::Light(LightTarget::Level(35))),
+        36 => (Walk, false, floor(F::HighestFloorPlus8, turbo)),
+        37 => (Walk, false, floor_change(F::LowestFloor, FLOORSPEED, ChangeModel::Numeric)),
+        38 => (Walk, false, floor(F::LowestFloor, FLOORSPEED)),
+        39 => (Walk, false, teleport(false)),
+        40 => (
+            Walk,
+            false,
+            Action::RaiseCeilingLowerFloor {
+                ceiling: C::HighestCeiling,
+                floor: F::LowestFloor,
+                speed: CEILSPEED,
+            },
+        ),
+        41 => (Switch, false, ceiling(C::Floor, false)),
+        42 => (Switch, true, door(Close, VDOORSPEED)),
+        43 => (Switch, true, ceiling(C::Floor, false)),
+        44 => (Walk, false, ceiling(C::FloorPlus8, true)),
+        45 => (Switch, true, floor(F::HighestFloor, FLOORSPEED)),
+        46 => (Gun, true, door(Open, VDOORSPEED)),
+        47 => (Gun, false, plat(PlatKind::RaiseToNearestAndChange, PLATSPEED / 2.)),
+        48 => (Passive, false, Action::Effect(Effect::ScrollLeft)),
+        49 => (Switch, false, Action::Crusher { speed: CEILSPEED, silent: false }),
+        50 => (Switch, false, door(Close, VDOORSPEED)),
+        51 => (Switch, false, Action::Exit { secret: true }),
+        52 => (Walk, false, Action::Exit { secret: false }),
+        53 => (Walk, false, plat(PlatKind::Perpetual, PLATSPEED)),
+        54 => (Walk, false, Action::StopPlat),
+        55 => (Switch, false, crush_floor(F::LowestCeilingMinus8)),
+        56 => (Walk, false, crush_floor(F::LowestCeilingMinus8)),
+        57 => (Walk, false, Action::StopCrusher),
+        58 => (Walk, false, floor(F::By(24), FLOORSPEED)),
+        59 => (Walk, false, floor_change(F::By(24), FLOORSPEED, ChangeModel::Trigger)),
+        60 => (Switch, true, floor(F::LowestFloor, FLOORSPEED)),
+        61 => (Switch, true, door(Open, VDOORSPEED)),
+        62 => (Switch, true, plat(PlatKind::DownWaitUp, PLATSPEED * 4.)),
+        63 => (Switch, true, door(OpenWaitClose, VDOORSPEED)),
+        64 => (Switch, true, floor(F::LowestCeiling, FLOORSPEED)),
+        65 => (Switch, true, crush_floor(F::LowestCeilingMinus8)),
+        66 => (Switch, true, plat(PlatKind::RaiseAndChange(24), PLATSPEED / 2.)),
+        67 => (Switch, true, plat(PlatKind::RaiseAndChange(32), PLATSPEED / 2.)),
+        68 => (Switch, true, plat(PlatKind::RaiseToNearestAndChange, PLATSPEED / 2.)),
+        69 => (Switch, true, floor(F::NextHigher, FLOORSPEED)),
+        70 => (Switch, true, floor(F::HighestFloorPlus8, turbo)),
+        71 => (Switch, false, floor(F::HighestFloorPlus8, turbo)),
+        72 => (Walk, true, ceiling(C::FloorPlus8, true)),
+        73 => (Walk, true, Action::Crusher { speed: CEILSPEED, silent: false }),
+        74 => (Walk, true, Action::StopCrusher),
+        75 => (Walk, true, door(Close, VDOORSPEED)),
+        76 => (Walk, true, door(CloseWaitOpen, VDOORSPEED)),
+        77 => (Walk, true, Action::Crusher { speed: CEILSPEED * 2., silent: false }),
+        79 => (Walk, true, Action::Light(LightTarget::Level(35))),
+        80 => (Walk, true, Action::Light(LightTarget::BrightestNeighbour)),
+        81 => (Walk, true, Action::Light(LightTarget::Level(255))),
+        82 => (Walk, true, floor(F::LowestFloor, FLOORSPEED)),
+        83 => (Walk, true, floor(F::HighestFloor, FLOORSPEED)),
+        84 => (Walk, true, floor_change(F::LowestFloor, FLOORSPEED, ChangeModel::Numeric)),
+        86 => (Walk, true, door(Open, VDOORSPEED)),
+        87 => (Walk, true, plat(PlatKind::Perpetual, PLATSPEED)),
+        88 => (Walk, true, plat(PlatKind::DownWaitUp, PLATSPEED * 4.)),
+        89 => (Walk, true, Action::StopPlat),
+        90 => (Walk, true, door(OpenWaitClose, VDOORSPEED)),
+        91 => (Walk, true, floor(F::LowestCeiling, FLOORSPEED)),
+        92 => (Walk, true, floor(F::By(24), FLOORSPEED)),
+        93 => (Walk, true, floor_change(F::By(24), FLOORSPEED, ChangeModel::Trigger)),
+        94 => (Walk, true, crush_floor(F::LowestCeilingMinus8)),
+        95 => (Walk, true, plat(PlatKind::RaiseToNearestAndChange, PLATSPEED / 2.)),
+        96 => (Walk, true, floor(F::ShortestLowerTexture, FLOORSPEED)),
+        97 => (Walk, true, teleport(false)),
+        98 => (Walk, true, floor(F::HighestFloorPlus8, turbo)),
+        99 => (Switch, true, locked(Open, blaze, Key::Blue)),
+        100 => (Walk, false, stairs(16, turbo)),
+        101 => (Switch, false, floor(F::LowestCeiling, FLOORSPEED)),
+        102 => (Switch, false, floor(F::HighestFloor, FLOORSPEED)),
+        103 => (Switch, false, door(Open, VDOORSPEED)),
+        104 => (Walk, false, Action::Light(LightTarget::DarkestNeighbour)),
+        105 => (Walk, true, door(OpenWaitClose, blaze)),
+        106 => (Walk, true, door(Open, blaze)),
+        107 => (Walk, true, door(Close, blaze)),
+        108 => (Walk, false, door(OpenWaitClose, blaze)),
+        109 => (Walk, false, door(Open, blaze)),
+        110 => (Walk, false, door(Close, blaze)),
+        111 => (Switch, false, door(OpenWaitClose, blaze)),
+        112 => (Switch, false, door(Open, blaze)),
+        113 => (Switch, false, door(Close, blaze)),
+        114 => (Switch, true, door(OpenWaitClose, blaze)),
+        115 => (Switch, true, door(Open, blaze)),
+        116 => (Switch, true, door(Close, blaze)),
+        117 => (Manual, true, door(OpenWaitClose, blaze)),
+        118 => (Manual, false, door(Open, blaze)),
+        119 => (Walk, false, floor(F::NextHigher, FLOORSPEED)),
+        120 => (Walk, true, plat(PlatKind::DownWaitUp, PLATSPEED * 8.)),
+        121 => (Walk, false, plat(PlatKind::DownWaitUp, PLATSPEED * 8.)),
+        122 => (Switch, false, plat(PlatKind::DownWaitUp, PLATSPEED * 8.)),
+        123 => (Switch, true, plat(PlatKind::DownWaitUp, PLATSPEED * 8.)),
+        124 => (Walk, false, Action::Exit { secret: true }),
+        125 => (Walk, false, teleport(true)),
+        126 => (Walk, true, teleport(true)),
+        127 => (Switch, false, stairs(16, turbo)),
+        128 => (Walk, true, floor(F::NextHigher, FLOORSPEED)),
+        129 => (Walk, true, floor(F::NextHigher, turbo)),
+        130 => (Walk, false, floor(F::NextHigher, turbo)),
+        131 => (Switch, false, floor(F::NextHigher, turbo)),
+        132 => (Switch, true, floor(F::NextHigher, turbo)),
+        133 => (Switch, false, locked(Open, blaze, Key::Blue)),
+        134 => (Switch, true, locked(Open, blaze, Key::Red)),
+        135 => (Switch, false, locked(Open, blaze, Key::Red)),
+        136 => (Switch, true, locked(Open, blaze, Key::Yellow)),
+        137 => (Switch, false, locked(Open, blaze, Key::Yellow)),
+        138 => (Switch, true, Action::Light(LightTarget::Level(255))),
+        139 => (Switch, true, Action::Light(LightTarget::Level(35))),
+        140 => (Switch, false, floor(F::By(512), FLOORSPEED)),
+        141 => (Walk, false, Action::Crusher { speed: CEILSPEED, silent: true }),
+        _ => return None,
+    };
+
+    Some(LineSpecial {
+        source: Source::Vanilla,
+        trigger,
+        repeatable,
+        monsters: matches!(special, 1 | 4 | 10 | 39 | 88 | 97 | 125 | 126),
+        action,
+    })
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let name = match self {
+            Key::Any => "Any Key",
+            Key::Blue => "Blue Key",
+            Key::Yellow => "Yellow Key",
+            Key::Red => "Red Key",
+            Key::BlueCard => "Blue Card",
+            Key::YellowCard => "Yellow Card",
+            Key::RedCard => "Red Card",
+            Key::BlueSkull => "Blue Skull",
+            Key::YellowSkull => "Yellow Skull",
+            Key::RedSkull => "Red Skull",
+            Key::AllThree => "All Three Keys",
+            Key::AllSix => "All Six Keys",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for FloorTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            FloorTarget::LowestCeiling => write!(f, "to Lowest Neighbour Ceiling"),
+            FloorTarget::LowestCeilingMinus8 => write!(f, "to 8 below Lowest Neighbour Ceiling"),
+            FloorTarget::NextHigher => write!(f, "to Next Higher Neighbour Floor"),
+            FloorTarget::NextLower => write!(f, "to Next Lower Neighbour Floor"),
+            FloorTarget::LowestFloor => write!(f, "to Lowest Neighbour Floor"),
+            FloorTarget::HighestFloor => write!(f, "to Highest Neighbour Floor"),
+            FloorTarget::HighestFloorPlus8 => write!(f, "to 8 above Highest Neighbour Floor"),
+            FloorTarget::Ceiling => write!(f, "to Ceiling"),
+            FloorTarget::By(amount) => write!(f, "by {}", amount.abs()),
+            FloorTarget::ShortestLowerTexture => write!(f, "by Shortest Lower Texture"),
+        }
+    }
+}
+
+impl Display for CeilingTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            CeilingTarget::Floor => write!(f, "to Floor"),
+            CeilingTarget::FloorPlus8 => write!(f, "to 8 above Floor"),
+            CeilingTarget::HighestCeiling => write!(f, "to Highest Neighbour Ceiling"),
+            CeilingTarget::LowestCeiling => write!(f, "to Lowest Neighbour Ceiling"),
+            CeilingTarget::NextHigher => write!(f, "to Next Higher Neighbour Ceiling"),
+            CeilingTarget::NextLower => write!(f, "to Next Lower Neighbour Ceiling"),
+            CeilingTarget::HighestFloor => write!(f, "to Highest Neighbour Floor"),
+            CeilingTarget::By(amount) => write!(f, "by {}", amount.abs()),
+            CeilingTarget::ShortestUpperTexture => write!(f, "by Shortest Upper Texture"),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let model = match self.model {
+            ChangeModel::Trigger => "trigger model",
+            ChangeModel::Numeric => "numeric model",
+        };
+
+        match self.sector_type {
+            TypeChange::Keep => write!(f, "change texture ({})", model),
+            TypeChange::Zero => write!(f, "change texture, clear type ({})", model),
+            TypeChange::Copy => write!(f, "change texture and type ({})", model),
+        }
+    }
+}
+
+impl Display for ScrollMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ScrollMode::Constant => Ok(()),
+            ScrollMode::Displacement => write!(f, " (displacement)"),
+            ScrollMode::Accelerative => write!(f, " (accelerative)"),
+        }
+    }
+}
+
+impl Display for Effect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Effect::ScrollLeft => write!(f, "Scroll Wall Left"),
+            Effect::ScrollRight => write!(f, "Scroll Wall Right"),
+            Effect::Scroll { target, mode } => {
+                let target = match target {
+                    ScrollTarget::Ceiling => "Scroll Ceiling",
+                    ScrollTarget::Floor => "Scroll Floor",
+                    ScrollTarget::Things => "Carry Things",
+                    ScrollTarget::FloorAndThings => "Scroll Floor and Carry Things",
+                    ScrollTarget::TaggedWalls => "Scroll Tagged Walls",
+                };
+                write!(f, "{}{}", target, mode)
+            }
+            Effect::ScrollByOffsets { tagged, mode } => write!(
+                f,
+                "Scroll {} by Offsets{}",
+                if *tagged { "Tagged Walls" } else { "Wall" },
+                mode
+            ),
+            Effect::TransferFloorLight => write!(f, "Transfer Floor Light"),
+            Effect::TransferCeilingLight => write!(f, "Transfer Ceiling Light"),
+            Effect::Friction => write!(f, "Friction"),
+            Effect::Wind => write!(f, "Wind"),
+            Effect::Current => write!(f, "Current"),
+            Effect::PointPusher => write!(f, "Point Pusher"),
+            Effect::FakeFloors => write!(f, "Fake Floors"),
+            Effect::Translucent => write!(f, "Translucent"),
+            Effect::TransferSky { flipped: false } => write!(f, "Transfer Sky"),
+            Effect::TransferSky { flipped: true } => write!(f, "Transfer Flipped Sky"),
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match *self {
+            Action::Door {
+                kind,
+                speed,
+                wait,
+                key,
+            } => {
+                let kind = match kind {
+                    DoorKind::OpenWaitClose => "Open Wait Close",
+                    DoorKind::Open => "Open Stay",
+                    DoorKind::Close => "Close Stay",
+                    DoorKind::CloseWaitOpen => "Close Wait Open",
+                };
+                write!(f, "Door {}, speed {}", kind, speed)?;
+                if wait > 0 {
+                    write!(f, ", wait {} tics", wait)?;
+                }
+                if let Some(key) = key {
+                    write!(f, ", {}", key)?;
+                }
+                Ok(())
+            }
+            Action::Plat { kind, speed, wait } => {
+                match kind {
+                    PlatKind::DownWaitUp => write!(f, "Lift Down Wait Up")?,
+                    PlatKind::DownToNextLowerWaitUp => write!(f, "Lift Down to Next Lower Floor Wait Up")?,
+                    PlatKind::DownToLowestCeilingWaitUp => write!(f, "Lift Down to Lowest Ceiling Wait Up")?,
+                    PlatKind::Perpetual => write!(f, "Lift Perpetual")?,
+                    PlatKind::Toggle => return write!(f, "Lift Toggle Floor to Ceiling"),
+                    PlatKind::RaiseToNearestAndChange => {
+                        return write!(f, "Floor Raise to Next Higher Floor and Change Texture, speed {}", speed)
+                    }
+                    PlatKind::RaiseAndChange(amount) => {
+                        return write!(f, "Floor Raise by {} and Change Texture, speed {}", amount, speed)
+                    }
+                }
+                write!(f, ", speed {}, wait {} tics", speed, wait)
+            }
+            Action::StopPlat => write!(f, "Lift Stop"),
+            Action::Floor {
+                target,
+                up,
+                speed,
+                crush,
+                change,
+            } => {
+                write!(
+                    f,
+                    "Floor {} {}, speed {}",
+                    if up { "Raise" } else { "Lower" },
+                    target,
+                    speed
+                )?;
+                if crush {
+                    write!(f, ", crush")?;
+                }
+                if let Some(change) = change {
+                    write!(f, ", {}", change)?;
+                }
+                Ok(())
+            }
+            Action::Ceiling {
+                target,
+                up,
+                speed,
+                crush,
+                change,
+            } => {
+                write!(
+                    f,
+                    "Ceiling {} {}, speed {}",
+                    if up { "Raise" } else { "Lower" },
+                    target,
+                    speed
+                )?;
+                if crush {
+                    write!(f, ", crush")?;
+                }
+                if let Some(change) = change {
+                    write!(f, ", {}", change)?;
+                }
+                Ok(())
+            }
+            Action::RaiseCeilingLowerFloor {
+                ceiling,
+                floor,
+                speed,
+            } => write!(
+                f,
+                "Ceiling Raise {} and Floor Lower {}, speed {}",
+                ceiling, floor, speed
+            ),
+            Action::Crusher { speed, silent } => {
+                write!(f, "Crusher Start, speed {}", speed)?;
+                if silent {
+                    write!(f, ", silent")?;
+                }
+                Ok(())
+            }
+            Action::StopCrusher => write!(f, "Crusher Stop"),
+            Action::Stairs {
+                step,
+                up,
+                speed,
+                ignore_texture,
+            } => {
+                write!(
+                    f,
+                    "Stairs Build {} by {}, speed {}",
+                    if up { "Up" } else { "Down" },
+                    step,
+                    speed
+                )?;
+                if ignore_texture {
+                    write!(f, ", ignore texture")?;
+                }
+                Ok(())
+            }
+            Action::Donut => write!(f, "Donut"),
+            Action::Elevator { target, speed } => {
+                let target = match target {
+                    ElevatorTarget::NextHigher => "Next Higher Floor",
+                    ElevatorTarget::NextLower => "Next Lower Floor",
+                    ElevatorTarget::Current => "Current Floor",
+                };
+                write!(f, "Elevator to {}, speed {}", target, speed)
+            }
+            Action::ChangeTexture(ChangeModel::Trigger) => {
+                write!(f, "Change Floor Texture and Type (trigger model)")
+            }
+            Action::ChangeTexture(ChangeModel::Numeric) => {
+                write!(f, "Change Floor Texture and Type (numeric model)")
+            }
+            Action::Light(target) => match target {
+                LightTarget::Level(level) => write!(f, "Light to {}", level),
+                LightTarget::BrightestNeighbour => write!(f, "Light to Brightest Neighbour"),
+                LightTarget::DarkestNeighbour => write!(f, "Light to Darkest Neighbour"),
+                LightTarget::Blink => write!(f, "Light Start Blinking"),
+            },
+            Action::Teleport {
+                monsters_only,
+                silent,
+                line,
+                reversed,
+            } => {
+                if silent {
+                    write!(f, "Silent ")?;
+                }
+                if reversed {
+                    write!(f, "Reversed ")?;
+                }
+                if line {
+                    write!(f, "Line ")?;
+                }
+                write!(f, "Teleport")?;
+                if monsters_only {
+                    write!(f, " (monsters only)")?;
+                }
+                Ok(())
+            }
+            Action::Exit { secret: false } => write!(f, "Exit"),
+            Action::Exit { secret: true } => write!(f, "Secret Exit"),
+            Action::Effect(effect) => write!(f, "{}", effect),
+        }
+    }
+}
+
+impl Display for LineSpecial {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let kind = match self.trigger {
+            Trigger::Walk => "W",
+            Trigger::Switch => "S",
+            Trigger::Manual => "D",
+            Trigger::Gun => "G",
+            Trigger::Passive => return write!(f, "{}", self.action),
+        };
+
+        write!(
+            f,
+            "{}{} {}",
+            kind,
+            if self.repeatable { "R" } else { "1" },
+            self.action
+        )?;
+
+        if self.monsters {
+            write!(f, " (monsters too)")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::mapmanager::MapManager;
+use crate::panel::{cleanup_panel, toggle_panel};
+use crate::state::wad_select::{HOVERED_BUTTON, NORMAL_BUTTON, PRESSED_BUTTON};
+use crate::state::GameState;
+use crate::AppState;
+use bevy::app::{App, Plugin};
+use bevy::prelude::*;
+
+pub struct MapPickerPlugin;
+
+#[derive(Component)]
+struct MapPickerPanel;
+
+#[derive(Component)]
+struct MapButton {
+    map_ind: i32,
+}
+
+type MapButtonInteractions<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static mut BackgroundColor, &'static MapButton),
+    (Changed<Interaction>, With<Button>),
+>;
+
+impl Plugin for MapPickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (toggle_picker, map_buttons, map_keys)
+                .distributive_run_if(resource_exists::<MapManager>())
+                .in_set(OnUpdate(GameState::MapView)),
+        )
+        .add_system(cleanup_panel::<MapPickerPanel>.in_schedule(OnExit(GameState::MapView)));
+    }
+}
+
+/// M shows the maps of the PWAD, built the first time it opens
+fn toggle_picker(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    manager: Res<MapManager>,
+    appstate: Res<AppState>,
+    mut panels: Query<&mut Style, With<MapPickerPanel>>,
+) {
+    let anchor = UiRect {
+        left: Val::Px(10.),
+        top: Val::Px(10.),
+        ..default()
+    };
+
+    let Some(panel) = toggle_panel(&mut commands, &keys, KeyCode::M, anchor, MapPickerPanel, &mut panels) else {
+        return;
+    };
+
+    for (map_ind, name) in manager.map_list() {
+        let current = map_ind == appstate.map_ind.max(0);
+
+        let button = commands
+            .spawn(ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(4.)),
+                    margin: UiRect::all(Val::Px(2.)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            })
+            .insert(MapButton { map_ind })
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    name,
+                    TextStyle {
+                        font: asset_server.load("FiraMono-Medium.ttf"),
+                        font_size: 20.0,
+                        color: if current {
+                            PRESSED_BUTTON
+                        } else {
+                            Color::WHITE
+                        },
+                    },
+                ));
+            })
+            .id();
+
+        commands.entity(panel).add_child(button);
+    }
+}
+
+fn switch_map(appstate: &mut AppState, state: &mut NextState<GameState>, map_ind: i32) {
+    info!("Switching to map {}", map_ind);
+
+    appstate.map_ind = map_ind;
+    // re-entering the state runs the cleanup and setup again
+    state.set(GameState::MapView);
+}
+
+fn map_buttons(
+    mut state: ResMut<NextState<GameState>>,
+    mut appstate: ResMut<AppState>,
+    mut interaction_query: MapButtonInteractions,
+) {
+    for (interaction, mut color, button) in &mut interaction_query {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = PRESSED_BUTTON.into();
+                switch_map(&mut appstate, &mut state, button.map_ind);
+            }
+            Interaction::Hovered => *color = HOVERED_BUTTON.into(),
+            Interaction::None => *color = NORMAL_BUTTON.into(),
+        }
+    }
+}
+
+/// ] and [ load the next and previous map, backspace goes back to the WAD select screen
+fn map_keys(
+    keys: Res<Input<KeyCode>>,
+    manager: Res<MapManager>,
+    mut state: ResMut<NextState<GameState>>,
+    mut appstate: ResMut<AppState>,
+) {
+    if keys.just_pressed(KeyCode::Back) {
+        state.set(GameState::WadSelect);
+        return;
+    }
+
+    let step: i32 = if keys.just_pressed(KeyCode::RBracket) {
+        1
+    } else if keys.just_pressed(KeyCode::LBracket) {
+        -1
+    } else {
+        return;
+    };
+
+    let maps = manager.map_list();
+
+    let Some(current) = maps
+        .iter()
+        .position(|(map_ind, _)| *map_ind == appstate.map_ind.max(0)) else {
+        return;
+    };
+
+    let next = current as i32 + step;
+
+    if next < 0 || next as usize >= maps.len() {
+        return;
+    }
+
+    switch_map(&mut appstate, &mut state, maps[next as usize].0);
+}
+
@@ -1,10 +1,12 @@
 mod wad_select;
+mod map_picker;
 mod map_view;
 
 use crate::state::wad_select::WadSelectPlugin;
 use bevy::app::{PluginGroup, PluginGroupBuilder};
 use bevy::prelude::{Component, States};
 
+use self::map_picker::MapPickerPlugin;
 use self::map_view::MapViewPlugin;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
@@ -23,6 +25,9 @@ pub struct StatePlugins;
 
 impl PluginGroup for StatePlugins {
     fn build(self) -> PluginGroupBuilder {
-        PluginGroupBuilder::start::<Self>().add(WadSelectPlugin).add(MapViewPlugin)
+        PluginGroupBuilder::start::<Self>()
+            .add(WadSelectPlugin)
+            .add(MapViewPlugin)
+            .add(MapPickerPlugin)
     }
 }
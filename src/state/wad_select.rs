@@ -6,9 +6,9 @@ use bevy::prelude::*;
 use native_dialog::FileDialog;
 use tinywad::wad::Wad;
 
-const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
-const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
-const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+pub(super) const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+pub(super) const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.25);
+pub(super) const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
 
 pub struct WadSelectPlugin;
 
@@ -50,10 +50,12 @@ fn mouse_scroll(
 struct GUIEl;
 
 fn wad_select_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0., 0., 5.).looking_at(Vec3::ZERO, Vec3::Y),
-        ..default()
-    });
+    commands
+        .spawn(Camera3dBundle {
+            transform: Transform::from_xyz(0., 0., 5.).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .insert(GUIEl);
 
     let base_container = commands
         .spawn(NodeBundle {
@@ -0,0 +1,259 @@
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use serde_json::{json, Value};
+
+use crate::mapmanager::complete_map::{trimmed, CompleteMap};
+use crate::mapmanager::MapManager;
+use crate::panel::{cleanup_panel, panel_text_bundle, toggle_panel};
+use crate::state::GameState;
+use crate::things::{monster_health, thing_category, ThingCategory};
+
+/// skill names and the thing flag that places a thing on them
+const SKILLS: [(&str, i16); 3] = [("Easy", 1), ("Medium", 2), ("Hard", 4)];
+
+/// MTF_NOTSINGLE, only spawned in multiplayer
+const MULTIPLAYER_ONLY: i16 = 16;
+
+/// vanilla secret sector or the Boom generalized secret bit
+pub fn is_secret_sector(special: i16) -> bool {
+    special == 9 || special & 128 != 0
+}
+
+/// what a single player finds on one skill level
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SkillStats {
+    pub monsters: usize,
+    pub hitpoints: u32,
+    pub items: usize,
+    pub weapons: usize,
+    pub keys: usize,
+}
+
+/// counts and contents of a map, computed from the parsed lumps alone
+pub struct MapStats {
+    pub vertices: usize,
+    pub linedefs: usize,
+    pub sidedefs: usize,
+    pub sectors: usize,
+    pub things: usize,
+    pub secrets: usize,
+    /// easy, medium and hard
+    pub skills: [SkillStats; 3],
+    pub textures: BTreeSet<String>,
+    pub flats: BTreeSet<String>,
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl MapStats {
+    pub fn new(map: &CompleteMap) -> Self {
+        let mut skills = [SkillStats::default(); 3];
+
+        for thing in &map.things_vec {
+            if thing.flags & MULTIPLAYER_ONLY != 0 {
+                continue;
+            }
+
+            let Some(category) = thing_category(thing.thing_type) else {
+                continue;
+            };
+
+            for (skill, (_, flag)) in skills.iter_mut().zip(SKILLS) {
+                if thing.flags & flag == 0 {
+                    continue;
+                }
+
+                match category {
+                    ThingCategory::Monster => {
+                        skill.monsters += 1;
+                        skill.hitpoints += monster_health(thing.thing_type).unwrap_or(0);
+                    }
+                    ThingCategory::Weapon => skill.weapons += 1,
+                    ThingCategory::Key => skill.keys += 1,
+                    category if category.is_item() => skill.items += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        let textures = map
+            .sidefef_vec
+            .iter()
+            .flat_map(|side| [&side.upper_tex, &side.mid_tex, &side.lower_tex])
+            .map(trimmed)
+            .filter(|name| !name.is_empty() && name != "-")
+            .map(|name| name.to_uppercase())
+            .collect();
+
+        let flats = map
+            .sector_vec
+            .iter()
+            .flat_map(|sector| [&sector.floor_tex, &sector.ceil_tex])
+            .map(|name| trimmed(name).to_uppercase())
+            .collect();
+
+        let (min, max) = if map.vert_vec.is_empty() {
+            (Vec2::ZERO, Vec2::ZERO)
+        } else {
+            map.vert_vec.iter().fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), vert| {
+                    let point = Vec2::new(vert.x as f32, vert.y as f32);
+                    (min.min(point), max.max(point))
+                },
+            )
+        };
+
+        MapStats {
+            vertices: map.vert_vec.len(),
+            linedefs: map.linedef_vec.len(),
+            sidedefs: map.sidefef_vec.len(),
+            sectors: map.sector_vec.len(),
+            things: map.things_vec.len(),
+            secrets: map
+                .sector_vec
+                .iter()
+                .filter(|sector| is_secret_sector(sector.special))
+                .count(),
+            skills,
+            textures,
+            flats,
+            min,
+            max,
+        }
+    }
+
+    /// report for scripts, see `export <out.json>`
+    pub fn to_json(&self, map_name: &str) -> Value {
+        let skills: serde_json::Map<String, Value> = SKILLS
+            .iter()
+            .zip(self.skills)
+            .map(|((name, _), skill)| {
+                (
+                    name.to_lowercase(),
+                    json!({
+                        "monsters": skill.monsters,
+                        "hitpoints": skill.hitpoints,
+                        "items": skill.items,
+                        "weapons": skill.weapons,
+                        "keys": skill.keys,
+                    }),
+                )
+            })
+            .collect();
+
+        json!({
+            "map": map_name,
+            "vertices": self.vertices,
+            "linedefs": self.linedefs,
+            "sidedefs": self.sidedefs,
+            "sectors": self.sectors,
+            "things": self.things,
+            "secrets": self.secrets,
+            "skills": skills,
+            "textures": self.textures,
+            "flats": self.flats,
+            "bounds": {
+                "min": [self.min.x, self.min.y],
+                "max": [self.max.x, self.max.y],
+            },
+        })
+    }
+
+    /// panel text, one column per skill
+    pub fn text(&self, map_name: &str) -> String {
+        let size = self.max - self.min;
+
+        let mut text = format!(
+            "{}\n  Vertices {}  Linedefs {}  Sidedefs {}\n  Sectors {}  Things {}  Secrets {}\n  Bounds {}, {} to {}, {} ({} x {})\n\n",
+            map_name,
+            self.vertices,
+            self.linedefs,
+            self.sidedefs,
+            self.sectors,
+            self.things,
+            self.secrets,
+            self.min.x,
+            self.min.y,
+            self.max.x,
+            self.max.y,
+            size.x,
+            size.y,
+        );
+
+        text += &format!("{:<10}", "");
+        for (name, _) in SKILLS {
+            text += &format!("{:>8}", name);
+        }
+
+        let row = |value: fn(&SkillStats) -> u32| self.skills.map(|skill| value(&skill));
+
+        let rows = [
+            ("Monsters", row(|s| s.monsters as u32)),
+            ("Hitpoints", row(|s| s.hitpoints)),
+            ("Items", row(|s| s.items as u32)),
+            ("Weapons", row(|s| s.weapons as u32)),
+            ("Keys", row(|s| s.keys as u32)),
+        ];
+
+        for (label, values) in rows {
+            text += &format!("\n{:<10}", label);
+            for value in values {
+                text += &format!("{:>8}", value);
+            }
+        }
+
+        for (label, names) in [("Textures", &self.textures), ("Flats", &self.flats)] {
+            text += &format!("\n\n{} ({})", label, names.len());
+
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            for line in names.chunks(6) {
+                text += &format!("\n  {}", line.join(" "));
+            }
+        }
+
+        text
+    }
+}
+
+#[derive(Component)]
+struct StatsPanel;
+
+/// I shows the statistics of the loaded map
+fn toggle_stats(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    manager: Res<MapManager>,
+    mut panels: Query<&mut Style, With<StatsPanel>>,
+) {
+    let anchor = UiRect {
+        left: Val::Px(10.),
+        bottom: Val::Px(10.),
+        ..default()
+    };
+
+    let Some(panel) = toggle_panel(&mut commands, &keys, KeyCode::I, anchor, StatsPanel, &mut panels) else {
+        return;
+    };
+
+    let text = MapStats::new(&manager.map).text(&manager.map_name);
+
+    commands.entity(panel).with_children(|panel| {
+        panel.spawn(panel_text_bundle(&asset_server, text));
+    });
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            toggle_stats
+                .run_if(resource_exists::<MapManager>())
+                .in_set(OnUpdate(GameState::MapView)),
+        )
+        .add_system(cleanup_panel::<StatsPanel>.in_schedule(OnExit(GameState::MapView)));
+    }
+}
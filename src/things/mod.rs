@@ -0,0 +1,348 @@
+use bevy::prelude::*;
+
+use crate::lighting::LitSurface;
+use crate::mapmanager::components::ThingEntity;
+use crate::mapmanager::MapManager;
+use crate::state::GameState;
+
+/// sprite prefix, frame and whether the thing hangs from the ceiling
+fn thing_sprite(thing_type: i16) -> Option<(&'static str, char, bool)> {
+    let sprite = match thing_type {
+        // players
+        1..=4 | 11 => ("PLAY", 'A', false),
+
+        // monsters
+        3004 => ("POSS", 'A', false),
+        9 => ("SPOS", 'A', false),
+        65 => ("CPOS", 'A', false),
+        3001 => ("TROO", 'A', false),
+        3002 | 58 => ("SARG", 'A', false),
+        3006 => ("SKUL", 'A', false),
+        3005 => ("HEAD", 'A', false),
+        69 => ("BOS2", 'A', false),
+        3003 => ("BOSS", 'A', false),
+        68 => ("BSPI", 'A', false),
+        71 => ("PAIN", 'A', false),
+        66 => ("SKEL", 'A', false),
+        67 => ("FATT", 'A', false),
+        64 => ("VILE", 'A', false),
+        7 => ("SPID", 'A', false),
+        16 => ("CYBR", 'A', false),
+        84 => ("SSWV", 'A', false),
+        72 => ("KEEN", 'A', true),
+        88 => ("BBRN", 'A', false),
+
+        // weapons
+        2005 => ("CSAW", 'A', false),
+        2001 => ("SHOT", 'A', false),
+        82 => ("SGN2", 'A', false),
+        2002 => ("MGUN", 'A', false),
+        2003 => ("LAUN", 'A', false),
+        2004 => ("PLAS", 'A', false),
+        2006 => ("BFUG", 'A', false),
+
+        // ammo
+        2007 => ("CLIP", 'A', false),
+        2048 => ("AMMO", 'A', false),
+        2008 => ("SHEL", 'A', false),
+        2049 => ("SBOX", 'A', false),
+        2010 => ("ROCK", 'A', false),
+        2046 => ("BROK", 'A', false),
+        2047 => ("CELL", 'A', false),
+        17 => ("CELP", 'A', false),
+        8 => ("BPAK", 'A', false),
+
+        // health, armor and powerups
+        2011 => ("STIM", 'A', false),
+        2012 => ("MEDI", 'A', false),
+        2014 => ("BON1", 'A', false),
+        2015 => ("BON2", 'A', false),
+        2018 => ("ARM1", 'A', false),
+        2019 => ("ARM2", 'A', false),
+        83 => ("MEGA", 'A', false),
+        2013 => ("SOUL", 'A', false),
+        2022 => ("PINV", 'A', false),
+        2023 => ("PSTR", 'A', false),
+        2024 => ("PINS", 'A', false),
+        2025 => ("SUIT", 'A', false),
+        2026 => ("PMAP", 'A', false),
+        2045 => ("PVIS", 'A', false),
+
+        // keys
+        5 => ("BKEY", 'A', false),
+        6 => ("YKEY", 'A', false),
+        13 => ("RKEY", 'A', false),
+        40 => ("BSKU", 'A', false),
+        39 => ("YSKU", 'A', false),
+        38 => ("RSKU", 'A', false),
+
+        // obstacles and decorations
+        2035 => ("BAR1", 'A', false),
+        70 => ("FCAN", 'A', false),
+        43 => ("TRE1", 'A', false),
+        47 => ("SMIT", 'A', false),
+        48 => ("ELEC", 'A', false),
+        54 => ("TRE2", 'A', false),
+        30 => ("COL1", 'A', false),
+        31 => ("COL2", 'A', false),
+        32 => ("COL3", 'A', false),
+        33 => ("COL4", 'A', false),
+        36 => ("COL5", 'A', false),
+        37 => ("COL6", 'A', false),
+        41 => ("CEYE", 'A', false),
+        42 => ("FSKU", 'A', false),
+        44 => ("TBLU", 'A', false),
+        45 => ("TGRN", 'A', false),
+        46 => ("TRED", 'A', false),
+        55 => ("SMBT", 'A', false),
+        56 => ("SMGT", 'A', false),
+        57 => ("SMRT", 'A', false),
+        34 => ("CAND", 'A', false),
+        35 => ("CBRA", 'A', false),
+        85 => ("TLMP", 'A', false),
+        86 => ("TLP2", 'A', false),
+        2028 => ("COLU", 'A', false),
+        25 => ("POL1", 'A', false),
+        26 => ("POL6", 'A', false),
+        27 => ("POL4", 'A', false),
+        28 => ("POL2", 'A', false),
+        29 => ("POL3", 'A', false),
+        24 => ("POL5", 'A', false),
+        79 => ("POB1", 'A', false),
+        80 => ("POB2", 'A', false),
+        81 => ("BRS1", 'A', false),
+
+        // corpses
+        10 | 12 => ("PLAY", 'W', false),
+        15 => ("PLAY", 'N', false),
+        18 => ("POSS", 'L', false),
+        19 => ("SPOS", 'L', false),
+        20 => ("TROO", 'M', false),
+        21 => ("SARG", 'N', false),
+        22 => ("HEAD", 'L', false),
+        23 => ("SKUL", 'K', false),
+
+        // hanging from the ceiling
+        49 | 63 => ("GOR1", 'A', true),
+        50 | 59 => ("GOR2", 'A', true),
+        51 | 61 => ("GOR3", 'A', true),
+        52 | 60 => ("GOR4", 'A', true),
+        53 | 62 => ("GOR5", 'A', true),
+        73 => ("HDB1", 'A', true),
+        74 => ("HDB2", 'A', true),
+        75 => ("HDB3", 'A', true),
+        76 => ("HDB4", 'A', true),
+        77 => ("HDB5", 'A', true),
+        78 => ("HDB6", 'A', true),
+
+        _ => return None,
+    };
+
+    Some(sprite)
+}
+
+/// players and monsters, the things that face somewhere
+pub fn is_actor(thing_type: i16) -> bool {
+    matches!(
+        thing_type,
+        1..=4
+            | 11
+            | 3004
+            | 9
+            | 65
+            | 3001
+            | 3002
+            | 58
+            | 3006
+            | 3005
+            | 69
+            | 3003
+            | 68
+            | 71
+            | 66
+            | 67
+            | 64
+            | 7
+            | 16
+            | 84
+    )
+}
+
+/// what a thing is for the map statistics
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ThingCategory {
+    Player,
+    Monster,
+    Weapon,
+    Ammo,
+    Health,
+    Armor,
+    Powerup,
+    Key,
+    Decoration,
+}
+
+impl ThingCategory {
+    /// pickups other than weapons and keys
+    pub fn is_item(self) -> bool {
+        matches!(
+            self,
+            ThingCategory::Ammo | ThingCategory::Health | ThingCategory::Armor | ThingCategory::Powerup
+        )
+    }
+}
+
+/// category of a thing type, None for types Doom does not know
+pub fn thing_category(thing_type: i16) -> Option<ThingCategory> {
+    let category = match thing_type {
+        1..=4 | 11 => ThingCategory::Player,
+        _ if monster_health(thing_type).is_some() => ThingCategory::Monster,
+        2005 | 2001 | 82 | 2002 | 2003 | 2004 | 2006 => ThingCategory::Weapon,
+        2007 | 2048 | 2008 | 2049 | 2010 | 2046 | 2047 | 17 | 8 => ThingCategory::Ammo,
+        2011 | 2012 | 2014 => ThingCategory::Health,
+        2015 | 2018 | 2019 => ThingCategory::Armor,
+        83 | 2013 | 2022 | 2023 | 2024 | 2025 | 2026 | 2045 => ThingCategory::Powerup,
+        5 | 6 | 13 | 38..=40 => ThingCategory::Key,
+        // spawn spot and shooter of the boss brain are never drawn
+        87 | 89 => ThingCategory::Decoration,
+        _ if thing_sprite(thing_type).is_some() => ThingCategory::Decoration,
+        _ => return None,
+    };
+
+    Some(category)
+}
+
+/// spawn health of a monster (info.c), None for anything else
+pub fn monster_health(thing_type: i16) -> Option<u32> {
+    let health = match thing_type {
+        3004 => 20,
+        9 => 30,
+        65 => 70,
+        3001 => 60,
+        3002 | 58 => 150,
+        3006 => 100,
+        3005 => 400,
+        69 => 500,
+        3003 => 1000,
+        68 => 500,
+        71 => 400,
+        66 => 300,
+        67 => 600,
+        64 => 700,
+        7 => 3000,
+        16 => 4000,
+        84 => 50,
+        72 => 100,
+        _ => return None,
+    };
+
+    Some(health)
+}
+
+/// blocking radius of a thing (info.c)
+pub fn thing_radius(thing_type: i16) -> f32 {
+    match thing_type {
+        1..=4 | 11 => 16.,
+        3002 | 58 => 30.,
+        3006 | 72 => 16.,
+        3005 | 71 => 31.,
+        69 | 3003 => 24.,
+        68 => 64.,
+        67 => 48.,
+        7 => 128.,
+        16 => 40.,
+        _ => 20.,
+    }
+}
+
+/// billboard quads for every thing with a known sprite
+pub fn spawn_things(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    images: &mut Assets<Image>,
+    materials: &mut Assets<StandardMaterial>,
+    manager: &mut MapManager,
+) {
+    for index in 0..manager.map.things_vec.len() {
+        let thing = manager.map.things_vec[index].clone();
+
+        let Some((prefix, frame, hanging)) = thing_sprite(thing.thing_type) else {
+            continue;
+        };
+
+        // frames without rotations end in 0, the rest are drawn from the front as 1
+        let image = [0, 1]
+            .iter()
+            .map(|rotation| format!("{}{}{}", prefix, frame, rotation))
+            .find_map(|name| {
+                let image = manager.get_image(images, name.clone()).ok()?;
+                Some((name, image))
+            });
+
+        let Some((name, image)) = image else {
+            error!("Could not get sprite {}{} for thing {}", prefix, frame, index);
+            continue;
+        };
+
+        // high resolution sprites keep the size of the sprite they replace
+        let size = manager
+            .world_size(&name)
+            .or_else(|| images.get(&image).map(|image| image.size()))
+            .unwrap_or(Vec2::ONE);
+
+        let position = Vec2::new(thing.x as f32, thing.y as f32);
+        let sector = manager.map.sector_at(position);
+
+        let height = match sector.map(|sector| &manager.map.sector_vec[sector]) {
+            Some(sector) if hanging => sector.ceil_height as f32 - size.y,
+            Some(sector) => sector.floor_height as f32,
+            None => 0.,
+        };
+
+        let material = materials.add(StandardMaterial {
+            base_color_texture: Some(image),
+            alpha_mode: AlphaMode::Mask(0.5),
+            cull_mode: None,
+            unlit: true,
+            ..default()
+        });
+
+        let mut entity = commands.spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Quad::new(size))),
+            material,
+            transform: Transform::from_xyz(-position.x, height + size.y / 2., position.y),
+            ..default()
+        });
+
+        entity.insert(ThingEntity { index });
+
+        if let Some(sector) = sector {
+            entity.insert(LitSurface { sector });
+        }
+    }
+}
+
+/// turn the sprites around the vertical axis to face the active camera
+fn face_camera(
+    cameras: Query<(&Camera, &GlobalTransform), Without<ThingEntity>>,
+    mut things: Query<&mut Transform, With<ThingEntity>>,
+) {
+    let Some((_, camera)) = cameras.iter().find(|(camera, _)| camera.is_active) else {
+        return;
+    };
+
+    let eye = camera.translation();
+
+    for mut transform in things.iter_mut() {
+        let to_eye = eye - transform.translation;
+        transform.rotation = Quat::from_rotation_y(to_eye.x.atan2(to_eye.z));
+    }
+}
+
+pub struct ThingsPlugin;
+
+impl Plugin for ThingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(face_camera.in_set(OnUpdate(GameState::MapView)));
+    }
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+/// Doom runs its game logic at a fixed 35 tics per second
+pub const TICRATE: f32 = 35.;
+
+#[derive(Resource, Default)]
+pub struct TicClock {
+    accumulator: f32,
+    /// tics that elapsed since the previous frame
+    pub tics: u32,
+    pub gametic: u64,
+}
+
+fn advance_tics(time: Res<Time>, mut clock: ResMut<TicClock>) {
+    clock.accumulator += time.delta_seconds() * TICRATE;
+    clock.tics = clock.accumulator as u32;
+    clock.accumulator -= clock.tics as f32;
+    clock.gametic += clock.tics as u64;
+}
+
+pub struct TicPlugin;
+
+impl Plugin for TicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TicClock>()
+            .add_system(advance_tics.in_base_set(CoreSet::PreUpdate));
+    }
+}
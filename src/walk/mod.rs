@@ -0,0 +1,281 @@
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use bevy::window::{CursorGrabMode, PrimaryWindow};
+
+use crate::automap::Automap;
+use crate::mapmanager::complete_map::CompleteMap;
+use crate::mapmanager::MapManager;
+use crate::movers::{Activation, SectorMovers};
+use crate::state::GameState;
+use crate::tic::TICRATE;
+
+/// eye height above the floor
+const VIEWHEIGHT: f32 = 41.;
+const PLAYER_HEIGHT: f32 = 56.;
+/// highest step the player can climb
+const MAX_STEP: f32 = 24.;
+const USERANGE: f32 = 64.;
+const MISSILERANGE: f32 = 2048.;
+/// map units per tic
+const WALK_SPEED: f32 = 8.;
+const RUN_SPEED: f32 = 16.;
+const MOUSE_SENSITIVITY: f32 = 0.003;
+/// ML_BLOCKING linedef flag
+const ML_BLOCKING: i16 = 1;
+
+/// Camera driven by the walk mode
+#[derive(Component)]
+pub struct WalkCamera;
+
+#[derive(Resource)]
+pub struct Walker {
+    pub enabled: bool,
+    /// map coordinates
+    pub position: Vec2,
+    /// radians, 0 faces east
+    pub angle: f32,
+    pub pitch: f32,
+}
+
+impl Walker {
+    /// stands on the player 1 start
+    pub fn new(map: &CompleteMap) -> Self {
+        let start = map.things_vec.iter().find(|thing| thing.thing_type == 1);
+
+        Walker {
+            enabled: false,
+            position: start.map_or(Vec2::ZERO, |thing| Vec2::new(thing.x as f32, thing.y as f32)),
+            angle: start.map_or(0., |thing| (thing.angle as f32).to_radians()),
+            pitch: 0.,
+        }
+    }
+
+    fn direction(&self) -> Vec2 {
+        Vec2::new(self.angle.cos(), self.angle.sin())
+    }
+
+    /// camera transform at eye height looking along angle and pitch
+    pub fn eye(&self, map: &CompleteMap) -> Transform {
+        let eye = self.floor(map) + VIEWHEIGHT;
+        let look = Vec3::new(
+            -self.angle.cos() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.angle.sin() * self.pitch.cos(),
+        );
+
+        Transform::from_xyz(-self.position.x, eye, self.position.y).looking_to(look, Vec3::Y)
+    }
+
+    fn floor(&self, map: &CompleteMap) -> f32 {
+        map.sector_at(self.position)
+            .map_or(0., |sector| map.sector_vec[sector].floor_height as f32)
+    }
+}
+
+/// the opening of a two-sided line, None when the line is solid
+fn opening(map: &CompleteMap, line: usize) -> Option<(f32, f32)> {
+    let (Some(front), Some(back)) = map.line_sectors(line) else {
+        return None;
+    };
+
+    let front = &map.sector_vec[front];
+    let back = &map.sector_vec[back];
+
+    Some((
+        front.floor_height.max(back.floor_height) as f32,
+        front.ceil_height.min(back.ceil_height) as f32,
+    ))
+}
+
+fn blocks_player(map: &CompleteMap, line: usize, feet: f32) -> bool {
+    if map.linedef_vec[line].flags & ML_BLOCKING != 0 {
+        return true;
+    }
+
+    match opening(map, line) {
+        Some((bottom, top)) => {
+            top - bottom < PLAYER_HEIGHT || bottom - feet > MAX_STEP || top - feet < PLAYER_HEIGHT
+        }
+        None => true,
+    }
+}
+
+/// lines crossed by a trace, nearest first
+fn trace(map: &CompleteMap, from: Vec2, to: Vec2) -> Vec<usize> {
+    let mut hits: Vec<(f32, usize)> = (0..map.linedef_vec.len())
+        .filter_map(|line| map.segment_intercept(line, from, to).map(|frac| (frac, line)))
+        .collect();
+
+    hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+    hits.into_iter().map(|(_, line)| line).collect()
+}
+
+fn toggle_walk(
+    keys: Res<Input<KeyCode>>,
+    mut walker: ResMut<Walker>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    walker.enabled = !walker.enabled;
+
+    if let Ok(mut window) = windows.get_single_mut() {
+        window.cursor.grab_mode = if walker.enabled {
+            CursorGrabMode::Locked
+        } else {
+            CursorGrabMode::None
+        };
+        window.cursor.visible = !walker.enabled;
+    }
+
+    info!("Walk mode {}", if walker.enabled { "on" } else { "off" });
+}
+
+fn walk(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mut mouse: EventReader<MouseMotion>,
+    mut walker: ResMut<Walker>,
+    mut movers: ResMut<SectorMovers>,
+    mut manager: ResMut<MapManager>,
+    mut cameras: Query<&mut Transform, With<WalkCamera>>,
+) {
+    if !walker.enabled {
+        mouse.clear();
+        return;
+    }
+
+    for motion in mouse.iter() {
+        walker.angle -= motion.delta.x * MOUSE_SENSITIVITY;
+        walker.pitch = (walker.pitch - motion.delta.y * MOUSE_SENSITIVITY).clamp(-1.5, 1.5);
+    }
+
+    let forward = walker.direction();
+    let right = Vec2::new(forward.y, -forward.x);
+    let mut wish = Vec2::ZERO;
+
+    if keys.any_pressed([KeyCode::W, KeyCode::Up]) {
+        wish += forward;
+    }
+    if keys.any_pressed([KeyCode::S, KeyCode::Down]) {
+        wish -= forward;
+    }
+    if keys.any_pressed([KeyCode::D, KeyCode::Right]) {
+        wish += right;
+    }
+    if keys.any_pressed([KeyCode::A, KeyCode::Left]) {
+        wish -= right;
+    }
+
+    let speed = if keys.pressed(KeyCode::LShift) {
+        RUN_SPEED
+    } else {
+        WALK_SPEED
+    };
+
+    let step = wish.normalize_or_zero() * speed * TICRATE * time.delta_seconds();
+
+    if step != Vec2::ZERO {
+        let map = &manager.map;
+        let from = walker.position;
+        let feet = walker.floor(map);
+
+        // slide along walls by dropping the blocked axis
+        let moved = [step, Vec2::new(step.x, 0.), Vec2::new(0., step.y)]
+            .into_iter()
+            .map(|step| from + step)
+            .find(|&to| {
+                !trace(map, from, to)
+                    .into_iter()
+                    .any(|line| blocks_player(map, line, feet))
+            });
+
+        if let Some(to) = moved {
+            let crossed: Vec<(usize, bool)> = trace(map, from, to)
+                .into_iter()
+                .map(|line| (line, map.point_on_front(line, from)))
+                .collect();
+
+            walker.position = to;
+
+            for (line, from_front) in crossed {
+                if manager.map.linedef_vec[line].special_type != 0 {
+                    movers.activate(&mut manager.map, line, Activation::Cross, from_front);
+                }
+            }
+        }
+    }
+
+    for mut transform in cameras.iter_mut() {
+        *transform = walker.eye(&manager.map);
+    }
+}
+
+/// use (space / E) and shoot (left click / ctrl) the lines in front of the walker
+fn interact(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    walker: Res<Walker>,
+    mut movers: ResMut<SectorMovers>,
+    mut manager: ResMut<MapManager>,
+) {
+    if !walker.enabled {
+        return;
+    }
+
+    let from = walker.position;
+
+    if keys.any_just_pressed([KeyCode::Space, KeyCode::E]) {
+        let to = from + walker.direction() * USERANGE;
+
+        for line in trace(&manager.map, from, to) {
+            if manager.map.linedef_vec[line].special_type != 0 {
+                let from_front = manager.map.point_on_front(line, from);
+                movers.activate(&mut manager.map, line, Activation::Use, from_front);
+                break;
+            }
+
+            match opening(&manager.map, line) {
+                Some((bottom, top)) if top > bottom => {}
+                _ => break,
+            }
+        }
+    }
+
+    if buttons.just_pressed(MouseButton::Left) || keys.just_pressed(KeyCode::LControl) {
+        let to = from + walker.direction() * MISSILERANGE;
+        let eye = walker.floor(&manager.map) + VIEWHEIGHT;
+
+        for line in trace(&manager.map, from, to) {
+            if manager.map.linedef_vec[line].special_type != 0 {
+                let from_front = manager.map.point_on_front(line, from);
+                movers.activate(&mut manager.map, line, Activation::Shoot, from_front);
+            }
+
+            match opening(&manager.map, line) {
+                Some((bottom, top)) if bottom < eye && top > eye => {}
+                _ => break,
+            }
+        }
+    }
+}
+
+/// the arrow keys pan the automap while it is open, walking would be blind
+fn automap_closed(automap: Option<Res<Automap>>) -> bool {
+    !automap.is_some_and(|automap| automap.enabled)
+}
+
+pub struct WalkPlugin;
+
+impl Plugin for WalkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (toggle_walk, walk.run_if(automap_closed), interact)
+                .chain()
+                .distributive_run_if(resource_exists::<Walker>())
+                .in_set(OnUpdate(GameState::MapView)),
+        );
+    }
+}